@@ -0,0 +1,206 @@
+// Finds the rook and bishop magics from scratch, so precomputed.rs can be checked and
+// regenerated instead of taken on trust. `tinatin magic` prints a replacement for it.
+
+use std::fmt::Write;
+
+use super::sliding::{bishop_moves, rook_moves, sliding_attacks};
+use crate::types::{BB, Bitboard, Direction, Square};
+use crate::utils::splitmix64;
+
+pub const DEFAULT_SEED: u64 = 1;
+
+// How long to look for a magic one bit smaller before settling for the one we have
+const SHRINK_TRIES: u32 = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slider {
+    Rook,
+    Bishop,
+}
+
+impl Slider {
+    const fn directions(self) -> [Direction; 4] {
+        match self {
+            Self::Rook => [
+                Direction::North,
+                Direction::South,
+                Direction::East,
+                Direction::West,
+            ],
+            Self::Bishop => [
+                Direction::NorthWest,
+                Direction::SouthWest,
+                Direction::NorthEast,
+                Direction::SouthEast,
+            ],
+        }
+    }
+
+    const fn moves(self, from: Square, occupied: Bitboard) -> Bitboard {
+        match self {
+            Self::Rook => rook_moves(from, occupied),
+            Self::Bishop => bishop_moves(from, occupied),
+        }
+    }
+}
+
+// The squares whose occupancy changes the attacks from `from`. The last square of a ray is
+// attacked whether or not something stands on it, so it is left out.
+pub fn relevant_mask(slider: Slider, from: Square) -> Bitboard {
+    let mut mask = BB(0);
+    for direction in slider.directions() {
+        let ray = sliding_attacks(from, BB(0), direction);
+        if ray.is_empty() {
+            continue;
+        }
+
+        let last = if direction.is_increasing() {
+            ray.msb()
+        } else {
+            ray.lsb()
+        };
+        mask |= ray & !last.to_bitboard();
+    }
+
+    mask
+}
+
+// Everything needed to try magics for one square
+pub struct SquareSearch {
+    pub mask: Bitboard,
+    // Every occupancy of the mask with the attacks it gives
+    occupancies: Vec<(Bitboard, Bitboard)>,
+    table: Vec<Option<Bitboard>>,
+}
+
+impl SquareSearch {
+    pub fn new(slider: Slider, from: Square) -> Self {
+        let mask = relevant_mask(slider, from);
+
+        // Carry-rippler, counts through the subsets of the mask and wraps back to 0
+        let mut occupancies = Vec::with_capacity(1 << mask.count());
+        let mut subset = 0u64;
+        loop {
+            occupancies.push((BB(subset), slider.moves(from, BB(subset))));
+            subset = subset.wrapping_sub(mask.0) & mask.0;
+            if subset == 0 {
+                break;
+            }
+        }
+
+        Self {
+            mask,
+            occupancies,
+            table: Vec::new(),
+        }
+    }
+
+    // Whether every occupancy gets a slot of its own out of `magic` and `shift`, or shares it
+    // with occupancies that give the same attacks
+    pub fn is_magic(&mut self, magic: u64, shift: u32) -> bool {
+        self.table.clear();
+        self.table.resize(1 << (64 - shift), None);
+
+        for &(occupied, attacks) in &self.occupancies {
+            let index = (occupied.0.wrapping_mul(magic) >> shift) as usize;
+            match self.table[index] {
+                None => self.table[index] = Some(attacks),
+                Some(other) if other == attacks => {}
+                Some(_) => return false,
+            }
+        }
+
+        true
+    }
+
+    pub fn find(&mut self, shift: u32, seed: &mut u64, tries: u32) -> Option<u64> {
+        for _ in 0..tries {
+            // Magics with few bits set are far more likely to work
+            let magic = splitmix64(seed) & splitmix64(seed) & splitmix64(seed);
+
+            // The top bits make up the index, too few of them set there can't spread out the
+            // occupancies
+            if (self.mask.0.wrapping_mul(magic) >> 56).count_ones() < 6 {
+                continue;
+            }
+
+            if self.is_magic(magic, shift) {
+                return Some(magic);
+            }
+        }
+
+        None
+    }
+}
+
+// A verified magic for every square, with the smallest shift that works. With `shrink` each
+// square also gets a bounded search for a magic with fewer index bits than mask bits.
+pub fn find_magics(slider: Slider, seed: &mut u64, shrink: bool) -> Vec<(Bitboard, u64, u32)> {
+    (0..64)
+        .map(|from| {
+            let mut search = SquareSearch::new(slider, unsafe { Square::from_int(from) });
+
+            let mut shift = 64 - search.mask.count();
+            let mut magic = search
+                .find(shift, seed, u32::MAX)
+                .expect("a magic with one slot per occupancy always exists");
+
+            if shrink {
+                while let Some(smaller) = search.find(shift + 1, seed, SHRINK_TRIES) {
+                    magic = smaller;
+                    shift += 1;
+                }
+            }
+
+            (search.mask, magic, shift)
+        })
+        .collect()
+}
+
+fn write_magics(out: &mut String, magics: &[(Bitboard, u64, u32)]) {
+    for row in magics.chunks(8) {
+        out.push_str("   ");
+        for (mask, magic, shift) in row {
+            write!(
+                out,
+                " Magic::new(BB(0x{:016x}u64), BB(0x{magic:016x}u64), {shift}),",
+                mask.0
+            )
+            .unwrap();
+        }
+        out.push('\n');
+    }
+}
+
+// The whole of precomputed.rs, from magics found with `seed`
+pub fn precomputed_rs(seed: u64, shrink: bool) -> String {
+    let mut state = seed;
+    let rooks = find_magics(Slider::Rook, &mut state, shrink);
+    let bishops = find_magics(Slider::Bishop, &mut state, shrink);
+
+    let mut out = format!(
+        "// Generated by `tinatin magic {seed}{}`\n",
+        if shrink { " --shrink" } else { "" }
+    );
+    out.push_str(
+        "#![allow(dead_code, clippy::unreadable_literal)]\n\
+         \n\
+         use crate::attacks::magic::{Magic, with_offsets};\n\
+         use crate::types::BB;\n\
+         \n\
+         #[rustfmt::skip]\n\
+         pub const ROOK_MAGICS: [Magic; 64] = with_offsets([\n",
+    );
+    write_magics(&mut out, &rooks);
+    out.push_str(
+        "], 0);\n\
+         \n\
+         // The bishops go right after the rooks\n\
+         #[rustfmt::skip]\n\
+         pub const BISHOP_MAGICS: [Magic; 64] = with_offsets([\n",
+    );
+    write_magics(&mut out, &bishops);
+    out.push_str("], ROOK_MAGICS[63].offset() + ROOK_MAGICS[63].table_size());\n");
+
+    out
+}
@@ -4,6 +4,8 @@ use cfg_if::cfg_if;
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Magic {
     pub mask: Bitboard,
+    // Where this square's attacks start in the attack table shared by all squares and pieces
+    offset: usize,
 
     #[cfg(not(target_feature = "bmi2"))]
     #[allow(clippy::struct_field_names)]
@@ -18,41 +20,105 @@ impl Magic {
             if #[cfg(not(target_feature = "bmi2"))] {
                 Magic {
                     mask: BB(0),
+                    offset: 0,
                     magic: BB(0),
                     shift: 0
                 }
             } else {
                 Magic {
                     mask: BB(0),
+                    offset: 0,
                 }
             }
         }
     }
 
+    // The same index as `index`, but usable in const code. With BMI2 that is PEXT done bit by
+    // bit, so tables built with it line up with the hardware instruction.
+    #[cfg_attr(
+        all(target_feature = "bmi2", not(feature = "const_sliding_attacks")),
+        allow(dead_code)
+    )]
     pub const fn const_index(&self, occupied: Bitboard) -> usize {
-        (((occupied.0 & self.mask.0).wrapping_mul(self.magic.0)) >> self.shift) as usize
+        cfg_if! {
+            if #[cfg(target_feature = "bmi2")] {
+                self.offset + pext(occupied.0, self.mask.0) as usize
+            } else {
+                self.offset
+                    + (((occupied.0 & self.mask.0).wrapping_mul(self.magic.0)) >> self.shift) as usize
+            }
+        }
     }
 
     #[allow(clippy::missing_const_for_fn)]
     pub fn index(&self, occupied: Bitboard) -> usize {
         cfg_if! {
             if #[cfg(target_feature = "bmi2")] {
-                std::arch::x86_64::_pext_u64(occupied.0, self.mask) as usize
+                // Only compiled in when the target has BMI2, see `main`
+                self.offset + unsafe { std::arch::x86_64::_pext_u64(occupied.0, self.mask.0) } as usize
             } else {
                 self.const_index(occupied)
             }
         }
     }
 
-    cfg_if! {
-        if #[cfg(not(target_feature = "bmi2"))] {
-            pub const fn new(mask: Bitboard, magic: Bitboard, shift: u32) -> Self {
-                Self { mask, magic, shift }
+    // How many slots of the attack table this square uses
+    pub const fn table_size(&self) -> usize {
+        cfg_if! {
+            if #[cfg(target_feature = "bmi2")] {
+                1 << self.mask.0.count_ones()
+            } else {
+                1 << (64 - self.shift)
             }
-        } else {
-            pub const fn new(mask: Bitboard) -> Self {
-                Self { mask }
+        }
+    }
+
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    // PEXT needs no magic or shift, they are ignored with BMI2 so the same tables work for both
+    #[cfg_attr(target_feature = "bmi2", allow(unused_variables))]
+    pub const fn new(mask: Bitboard, magic: Bitboard, shift: u32) -> Self {
+        cfg_if! {
+            if #[cfg(not(target_feature = "bmi2"))] {
+                Self { mask, offset: 0, magic, shift }
+            } else {
+                Self { mask, offset: 0 }
             }
         }
     }
 }
+
+// Gathers the bits of `x` under `mask` into the low bits, in order
+#[cfg_attr(not(target_feature = "bmi2"), allow(dead_code))]
+pub const fn pext(x: u64, mut mask: u64) -> u64 {
+    let mut result = 0;
+
+    let mut bit = 0;
+    while mask != 0 {
+        let lowest = mask & mask.wrapping_neg();
+        if x & lowest != 0 {
+            result |= 1 << bit;
+        }
+
+        mask ^= lowest;
+        bit += 1;
+    }
+
+    result
+}
+
+// Lays out the squares of `magics` one after the other in the attack table, from `start` on
+pub const fn with_offsets(mut magics: [Magic; 64], start: usize) -> [Magic; 64] {
+    let mut offset = start;
+
+    let mut i = 0;
+    while i < magics.len() {
+        magics[i].offset = offset;
+        offset += magics[i].table_size();
+        i += 1;
+    }
+
+    magics
+}
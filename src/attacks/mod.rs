@@ -1,16 +1,22 @@
+pub mod finder;
 pub mod magic;
 mod non_sliding;
 mod precomputed;
 mod sliding;
+#[cfg(test)]
+mod test;
 
 use crate::board::Board;
 use crate::types::{BB, Bitboard, Color, Direction, Piece, Square};
 use non_sliding::NON_SLIDING_ATTACKS;
 use precomputed::{BISHOP_MAGICS, ROOK_MAGICS};
-use sliding::{BISHOP_ATTACKS_TABLE, ROOK_ATTACKS_TABLE};
+pub use sliding::init;
+use sliding::table_attacks;
 
 #[allow(clippy::cast_lossless)]
 impl Piece {
+    // Slider attacks come from a table that `init` fills in. Building a `Board` calls it, code
+    // that never does has to call it itself first.
     #[inline]
     pub fn attacks(self, from: Square, occupied: Bitboard) -> Bitboard {
         let from = from.as_int() as usize;
@@ -20,22 +26,18 @@ impl Piece {
         }
 
         let t = unsafe { Self::from_int(self.type_of() as i8) };
-        #[allow(unused_unsafe)]
-        unsafe {
-            match t {
-                Piece::WKnight => NON_SLIDING_ATTACKS[2][from],
-                Piece::WBishop => BISHOP_ATTACKS_TABLE[from][BISHOP_MAGICS[from].index(occupied)],
-                Piece::WRook => ROOK_ATTACKS_TABLE[from][ROOK_MAGICS[from].index(occupied)],
-                Piece::WQueen => BB(BISHOP_ATTACKS_TABLE[from]
-                    [BISHOP_MAGICS[from].index(occupied)]
-                .0 | ROOK_ATTACKS_TABLE[from]
-                    [ROOK_MAGICS[from].index(occupied)]
-                .0),
-                Piece::WKing => NON_SLIDING_ATTACKS[3][from],
-
-                _ => {
-                    unreachable!();
-                }
+        match t {
+            Piece::WKnight => NON_SLIDING_ATTACKS[2][from],
+            Piece::WBishop => table_attacks(BISHOP_MAGICS[from].index(occupied)),
+            Piece::WRook => table_attacks(ROOK_MAGICS[from].index(occupied)),
+            Piece::WQueen => {
+                table_attacks(BISHOP_MAGICS[from].index(occupied))
+                    | table_attacks(ROOK_MAGICS[from].index(occupied))
+            }
+            Piece::WKing => NON_SLIDING_ATTACKS[3][from],
+
+            _ => {
+                unreachable!();
             }
         }
     }
@@ -60,10 +62,30 @@ const fn piece_can_travel_in_sliding_direction(piece: Piece, direction: Directio
 // Implemented in a way that can be turned into a const function
 #[allow(clippy::cast_lossless)]
 impl Board {
+    #[allow(dead_code)]
     pub const fn get_attacks<const SIDE: bool>(&self) -> Bitboard {
         self.state.attacks[Color(SIDE).0 as usize]
     }
 
+    // Every piece of either color that attacks `square` when the board is `occupied`. Pieces
+    // missing from `occupied` still show up, mask them out if they are meant to be gone.
+    pub fn attackers_to(&self, square: Square, occupied: Bitboard) -> Bitboard {
+        let bb = |piece: Piece| self.piece_bb[piece.to_index()];
+        let to = square.as_int() as usize;
+
+        let queens = bb(Piece::WQueen) | bb(Piece::BQueen);
+        let diagonal = bb(Piece::WBishop) | bb(Piece::BBishop) | queens;
+        let straight = bb(Piece::WRook) | bb(Piece::BRook) | queens;
+
+        // A white pawn attacks `square` from where a black pawn on it would attack
+        (NON_SLIDING_ATTACKS[1][to] & bb(Piece::WPawn))
+            | (NON_SLIDING_ATTACKS[0][to] & bb(Piece::BPawn))
+            | (NON_SLIDING_ATTACKS[2][to] & (bb(Piece::WKnight) | bb(Piece::BKnight)))
+            | (NON_SLIDING_ATTACKS[3][to] & (bb(Piece::WKing) | bb(Piece::BKing)))
+            | (Piece::WBishop.attacks(square, occupied) & diagonal)
+            | (Piece::WRook.attacks(square, occupied) & straight)
+    }
+
     fn populate_attacks(&mut self) {
         self.state.attacks = {
             let mut ar = [BB(0); 2];
@@ -79,14 +101,17 @@ impl Board {
                 })
                 .to_index()];
 
+                // The enemy king is taken off the board, so that squares behind it on a
+                // checking ray still count as attacked and the king can't step back along it.
+                let occupied = BB((self.color_bb[0].0 | self.color_bb[1].0) & !king.0);
+
                 let mut index = 6 * (Color::WHITE.0 != side.0) as usize;
                 let mut check_count = 0;
                 while index < if side.0 == Color::WHITE.0 { 6 } else { 12 } {
                     let mut piece = self.piece_bb[index];
                     while piece.0 != BB(0).0 {
                         let square = unsafe { Square::from_int(piece.0.trailing_zeros() as u8) };
-                        let attacks = Piece::from_index(index)
-                            .attacks(square, BB(self.color_bb[0].0 | self.color_bb[1].0));
+                        let attacks = Piece::from_index(index).attacks(square, occupied);
 
                         if check_count < 3
                             && king.0 & attacks.0 != 0
@@ -123,6 +148,11 @@ impl Board {
                 Piece::BKing
             }
             .to_index()];
+            if king.0 == 0 {
+                side += 1;
+                continue;
+            }
+
             let king_square = unsafe { Square::from_int(king.0.trailing_zeros() as u8) };
 
             let directions = [
@@ -186,6 +216,10 @@ impl Board {
     }
 
     pub(crate) fn populate_state(&mut self) {
+        self.state.checkers = BB(0);
+        self.state.king_blockers = [BB(0); 2];
+        self.state.pinners = [BB(0); 2];
+
         self.populate_attacks();
         self.populate_pinners_and_blockers();
     }
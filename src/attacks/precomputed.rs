@@ -1,28 +1,30 @@
-// Stolen from integral
+// Generated by `tinatin magic 1`
 #![allow(dead_code, clippy::unreadable_literal)]
 
-use crate::{attacks::magic::Magic, types::BB};
+use crate::attacks::magic::{Magic, with_offsets};
+use crate::types::BB;
 
 #[rustfmt::skip]
-pub const ROOK_MAGICS: [Magic; 64] = [
-    Magic::new(BB(0x000101010101017eu64), BB(0x2480001240002088u64), 52), Magic::new(BB(0x000202020202027cu64), BB(0x0040002000405000u64), 53), Magic::new(BB(0x000404040404047au64), BB(0x2080088010012001u64), 53), Magic::new(BB(0x0008080808080876u64), BB(0x0080049001080080u64), 53), Magic::new(BB(0x001010101010106eu64), BB(0x0b00020800945100u64), 53), Magic::new(BB(0x002020202020205eu64), BB(0x04800a0080211400u64), 53), Magic::new(BB(0x004040404040403eu64), BB(0xc0801a0000800100u64), 53), Magic::new(BB(0x008080808080807eu64), BB(0x0600084300802204u64), 52),
-    Magic::new(BB(0x0001010101017e00u64), BB(0x4240800140006080u64), 53), Magic::new(BB(0x0002020202027c00u64), BB(0x0889002080400111u64), 54), Magic::new(BB(0x0004040404047a00u64), BB(0x4823004210200100u64), 54), Magic::new(BB(0x0008080808087600u64), BB(0xa000808008001000u64), 54), Magic::new(BB(0x0010101010106e00u64), BB(0x0020800800340082u64), 54), Magic::new(BB(0x0020202020205e00u64), BB(0x004100040039004eu64), 54), Magic::new(BB(0x0040404040403e00u64), BB(0x0802003408150200u64), 54), Magic::new(BB(0x0080808080807e00u64), BB(0x0800800080104100u64), 53),
-    Magic::new(BB(0x00010101017e0100u64), BB(0x0040008002245080u64), 53), Magic::new(BB(0x00020202027c0200u64), BB(0x0030004003402000u64), 54), Magic::new(BB(0x00040404047a0400u64), BB(0x2400110042600100u64), 54), Magic::new(BB(0x0008080808760800u64), BB(0xa000808008001000u64), 54), Magic::new(BB(0x00101010106e1000u64), BB(0x0123030004080010u64), 54), Magic::new(BB(0x00202020205e2000u64), BB(0x10c0808012000400u64), 54), Magic::new(BB(0x00404040403e4000u64), BB(0x0200040022080110u64), 54), Magic::new(BB(0x00808080807e8000u64), BB(0x028a420000a10844u64), 53),
-    Magic::new(BB(0x000101017e010100u64), BB(0x8040028480014021u64), 53), Magic::new(BB(0x000202027c020200u64), BB(0x0841006d00400082u64), 54), Magic::new(BB(0x000404047a040400u64), BB(0x0803004300146004u64), 54), Magic::new(BB(0x0008080876080800u64), BB(0x0000500280080080u64), 54), Magic::new(BB(0x001010106e101000u64), BB(0x0806140080080081u64), 54), Magic::new(BB(0x002020205e202000u64), BB(0x0014820080800400u64), 54), Magic::new(BB(0x004040403e404000u64), BB(0x1004084400021001u64), 54), Magic::new(BB(0x008080807e808000u64), BB(0x0800800080104100u64), 53),
-    Magic::new(BB(0x0001017e01010100u64), BB(0x0080800301002041u64), 53), Magic::new(BB(0x0002027c02020200u64), BB(0x0030004003402000u64), 54), Magic::new(BB(0x0004047a04040400u64), BB(0x9200502001004102u64), 54), Magic::new(BB(0x0008087608080800u64), BB(0x0c0200100a004020u64), 54), Magic::new(BB(0x0010106e10101000u64), BB(0x0141018801001004u64), 54), Magic::new(BB(0x0020205e20202000u64), BB(0x2248040080801a00u64), 54), Magic::new(BB(0x0040403e40404000u64), BB(0x0000100804000201u64), 54), Magic::new(BB(0x0080807e80808000u64), BB(0x0806004086002514u64), 53),
-    Magic::new(BB(0x00017e0101010100u64), BB(0x10807880c0008000u64), 53), Magic::new(BB(0x00027c0202020200u64), BB(0x0800200250004002u64), 54), Magic::new(BB(0x00047a0404040400u64), BB(0x0102100020008080u64), 54), Magic::new(BB(0x0008760808080800u64), BB(0x8200300021010009u64), 54), Magic::new(BB(0x00106e1010101000u64), BB(0x6280040801010010u64), 54), Magic::new(BB(0x00205e2020202000u64), BB(0x0020844010280120u64), 54), Magic::new(BB(0x00403e4040404000u64), BB(0x00000a0108040010u64), 54), Magic::new(BB(0x00807e8080808000u64), BB(0x0000040260820001u64), 53),
-    Magic::new(BB(0x007e010101010100u64), BB(0x0040008002245080u64), 53), Magic::new(BB(0x007c020202020200u64), BB(0x0010600092c00b80u64), 54), Magic::new(BB(0x007a040404040400u64), BB(0x8006900188200080u64), 54), Magic::new(BB(0x0076080808080800u64), BB(0x0080601202084200u64), 54), Magic::new(BB(0x006e101010101000u64), BB(0x4424008115880080u64), 54), Magic::new(BB(0x005e202020202000u64), BB(0x0002000408308600u64), 54), Magic::new(BB(0x003e404040404000u64), BB(0x0100080201100400u64), 54), Magic::new(BB(0x007e808080808000u64), BB(0x008005025c940a00u64), 53),
-    Magic::new(BB(0x7e01010101010100u64), BB(0x0220409300208001u64), 52), Magic::new(BB(0x7c02020202020200u64), BB(0x8002400980241101u64), 53), Magic::new(BB(0x7a04040404040400u64), BB(0x5402005280200842u64), 53), Magic::new(BB(0x7608080808080800u64), BB(0x3801000850000423u64), 53), Magic::new(BB(0x6e10101010101000u64), BB(0x300a002008043142u64), 53), Magic::new(BB(0x5e20202020202000u64), BB(0x1009000400220821u64), 53), Magic::new(BB(0x3e40404040404000u64), BB(0x9240028810012224u64), 53), Magic::new(BB(0x7e80808080808000u64), BB(0x0001940028410092u64), 52),
-];
+pub const ROOK_MAGICS: [Magic; 64] = with_offsets([
+    Magic::new(BB(0x000101010101017eu64), BB(0x01800088e0114000u64), 52), Magic::new(BB(0x000202020202027cu64), BB(0x0440004820001000u64), 53), Magic::new(BB(0x000404040404047au64), BB(0x0c80081000802000u64), 53), Magic::new(BB(0x0008080808080876u64), BB(0x8880080010000480u64), 53), Magic::new(BB(0x001010101010106eu64), BB(0x1200085060048200u64), 53), Magic::new(BB(0x002020202020205eu64), BB(0x018004000e000180u64), 53), Magic::new(BB(0x004040404040403eu64), BB(0x2400024100841008u64), 53), Magic::new(BB(0x008080808080807eu64), BB(0x808000450001a280u64), 52),
+    Magic::new(BB(0x0001010101017e00u64), BB(0x0212800022c00080u64), 53), Magic::new(BB(0x0002020202027c00u64), BB(0x0002004100220082u64), 54), Magic::new(BB(0x0004040404047a00u64), BB(0x8082801000822002u64), 54), Magic::new(BB(0x0008080808087600u64), BB(0x1002002040081200u64), 54), Magic::new(BB(0x0010101010106e00u64), BB(0x0820800400080081u64), 54), Magic::new(BB(0x0020202020205e00u64), BB(0x0801000204010008u64), 54), Magic::new(BB(0x0040404040403e00u64), BB(0x0094801100020080u64), 54), Magic::new(BB(0x0080808080807e00u64), BB(0x2012800080004500u64), 53),
+    Magic::new(BB(0x00010101017e0100u64), BB(0x0090908000400029u64), 53), Magic::new(BB(0x00020202027c0200u64), BB(0x0480220042008100u64), 54), Magic::new(BB(0x00040404047a0400u64), BB(0x0008420010842204u64), 54), Magic::new(BB(0x0008080808760800u64), BB(0x0020808010000800u64), 54), Magic::new(BB(0x00101010106e1000u64), BB(0x0604008008000680u64), 54), Magic::new(BB(0x00202020205e2000u64), BB(0x0082008080040002u64), 54), Magic::new(BB(0x00404040403e4000u64), BB(0x0a01808001000200u64), 54), Magic::new(BB(0x00808080807e8000u64), BB(0x1c00060000830264u64), 53),
+    Magic::new(BB(0x000101017e010100u64), BB(0x1085400480008020u64), 53), Magic::new(BB(0x000202027c020200u64), BB(0x1020100040004020u64), 54), Magic::new(BB(0x000404047a040400u64), BB(0x8450200500110440u64), 54), Magic::new(BB(0x0008080876080800u64), BB(0x00c8100100200902u64), 54), Magic::new(BB(0x001010106e101000u64), BB(0x0000080080800400u64), 54), Magic::new(BB(0x002020205e202000u64), BB(0x0002000200041009u64), 54), Magic::new(BB(0x004040403e404000u64), BB(0x80a0028400100841u64), 54), Magic::new(BB(0x008080807e808000u64), BB(0x0004088200006c01u64), 53),
+    Magic::new(BB(0x0001017e01010100u64), BB(0x0480082010400040u64), 53), Magic::new(BB(0x0002027c02020200u64), BB(0x0040080020201000u64), 54), Magic::new(BB(0x0004047a04040400u64), BB(0x0408104101002000u64), 54), Magic::new(BB(0x0008087608080800u64), BB(0x4018001000800880u64), 54), Magic::new(BB(0x0010106e10101000u64), BB(0x1484040080800800u64), 54), Magic::new(BB(0x0020205e20202000u64), BB(0x0100800200800400u64), 54), Magic::new(BB(0x0040403e40404000u64), BB(0x8002002182004408u64), 54), Magic::new(BB(0x0080807e80808000u64), BB(0x1120004102000084u64), 53),
+    Magic::new(BB(0x00017e0101010100u64), BB(0xc840044080248008u64), 53), Magic::new(BB(0x00027c0202020200u64), BB(0x0010002000414000u64), 54), Magic::new(BB(0x00047a0404040400u64), BB(0x0410008020008010u64), 54), Magic::new(BB(0x0008760808080800u64), BB(0x4008020100101000u64), 54), Magic::new(BB(0x00106e1010101000u64), BB(0x8001014800110024u64), 54), Magic::new(BB(0x00205e2020202000u64), BB(0xa002000400028080u64), 54), Magic::new(BB(0x00403e4040404000u64), BB(0x0432080201040010u64), 54), Magic::new(BB(0x00807e8080808000u64), BB(0x8800010080420004u64), 53),
+    Magic::new(BB(0x007e010101010100u64), BB(0x4100800510204300u64), 53), Magic::new(BB(0x007c020202020200u64), BB(0x8080812542090200u64), 54), Magic::new(BB(0x007a040404040400u64), BB(0x010a820012244200u64), 54), Magic::new(BB(0x0076080808080800u64), BB(0x0000800800100080u64), 54), Magic::new(BB(0x006e101010101000u64), BB(0x9312011020040a00u64), 54), Magic::new(BB(0x005e202020202000u64), BB(0x2044000480020080u64), 54), Magic::new(BB(0x003e404040404000u64), BB(0x0100820108100400u64), 54), Magic::new(BB(0x007e808080808000u64), BB(0x2088210400508200u64), 53),
+    Magic::new(BB(0x7e01010101010100u64), BB(0x0005412180083101u64), 52), Magic::new(BB(0x7c02020202020200u64), BB(0x0020290084104001u64), 53), Magic::new(BB(0x7a04040404040400u64), BB(0x0000811088c02202u64), 53), Magic::new(BB(0x7608080808080800u64), BB(0x0443002110000489u64), 53), Magic::new(BB(0x6e10101010101000u64), BB(0x2042001120040802u64), 53), Magic::new(BB(0x5e20202020202000u64), BB(0x0021000802040001u64), 53), Magic::new(BB(0x3e40404040404000u64), BB(0x0008100082410804u64), 53), Magic::new(BB(0x7e80808080808000u64), BB(0x010000610284004eu64), 52),
+], 0);
 
+// The bishops go right after the rooks
 #[rustfmt::skip]
-pub const BISHOP_MAGICS: [Magic; 64] = [
-    Magic::new(BB(0x0040201008040200u64), BB(0x0040440812022420u64), 58), Magic::new(BB(0x0000402010080400u64), BB(0x3209080800504480u64), 59), Magic::new(BB(0x0000004020100a00u64), BB(0x401000a189000040u64), 59), Magic::new(BB(0x0000000040221400u64), BB(0x2104070200108490u64), 59), Magic::new(BB(0x0000000002442800u64), BB(0x4004242000403041u64), 59), Magic::new(BB(0x0000000204085000u64), BB(0x0001012010880821u64), 59), Magic::new(BB(0x0000020408102000u64), BB(0x242108084a490101u64), 59), Magic::new(BB(0x0002040810204000u64), BB(0x200202b108080400u64), 58),
-    Magic::new(BB(0x0020100804020000u64), BB(0x0000044448020400u64), 59), Magic::new(BB(0x0040201008040000u64), BB(0xc200088808208020u64), 59), Magic::new(BB(0x00004020100a0000u64), BB(0x0000105182810000u64), 59), Magic::new(BB(0x0000004022140000u64), BB(0x180078204c400000u64), 59), Magic::new(BB(0x0000000244280000u64), BB(0x0001060a10005040u64), 59), Magic::new(BB(0x0000020408500000u64), BB(0x00c0950120100000u64), 59), Magic::new(BB(0x0002040810200000u64), BB(0x0000050110026100u64), 59), Magic::new(BB(0x0004081020400000u64), BB(0x200202b108080400u64), 59),
-    Magic::new(BB(0x0010080402000200u64), BB(0x4808000410640820u64), 59), Magic::new(BB(0x0020100804000400u64), BB(0x10200a0206020e00u64), 59), Magic::new(BB(0x004020100a000a00u64), BB(0x1002001003220020u64), 57), Magic::new(BB(0x0000402214001400u64), BB(0x0808000082004000u64), 57), Magic::new(BB(0x0000024428002800u64), BB(0x2001001820280200u64), 57), Magic::new(BB(0x0002040850005000u64), BB(0x1101008201030552u64), 57), Magic::new(BB(0x0004081020002000u64), BB(0x0151000245082000u64), 59), Magic::new(BB(0x0008102040004000u64), BB(0x0001001024032462u64), 59),
-    Magic::new(BB(0x0008040200020400u64), BB(0x4820042010942810u64), 59), Magic::new(BB(0x0010080400040800u64), BB(0x00a8210082040900u64), 59), Magic::new(BB(0x0020100a000a1000u64), BB(0x8180a08010010040u64), 57), Magic::new(BB(0x0040221400142200u64), BB(0xc24004002a010810u64), 55), Magic::new(BB(0x0002442800284400u64), BB(0x0003001001004012u64), 55), Magic::new(BB(0x0004085000500800u64), BB(0x0010038022405020u64), 57), Magic::new(BB(0x0008102000201000u64), BB(0x01042100040a8200u64), 59), Magic::new(BB(0x0010204000402000u64), BB(0x0000444049040200u64), 59),
-    Magic::new(BB(0x0004020002040800u64), BB(0x2001101000882008u64), 59), Magic::new(BB(0x0008040004081000u64), BB(0x0158026600580800u64), 59), Magic::new(BB(0x00100a000a102000u64), BB(0x4001680804440061u64), 57), Magic::new(BB(0x0022140014224000u64), BB(0x4000400a00042200u64), 55), Magic::new(BB(0x0044280028440200u64), BB(0x0000420021020080u64), 55), Magic::new(BB(0x0008500050080400u64), BB(0x0010020020020080u64), 57), Magic::new(BB(0x0010200020100800u64), BB(0x5008010040210800u64), 59), Magic::new(BB(0x0020400040201000u64), BB(0x000604002001108cu64), 59),
-    Magic::new(BB(0x0002000204081000u64), BB(0x0808010920050821u64), 59), Magic::new(BB(0x0004000408102000u64), BB(0x4000c41220020800u64), 59), Magic::new(BB(0x000a000a10204000u64), BB(0x4000804040400808u64), 57), Magic::new(BB(0x0014001422400000u64), BB(0x0000814200809802u64), 57), Magic::new(BB(0x0028002844020000u64), BB(0x0000080104020041u64), 57), Magic::new(BB(0x0050005008040200u64), BB(0x0414010c42000101u64), 57), Magic::new(BB(0x0020002010080400u64), BB(0x3209080800504480u64), 59), Magic::new(BB(0x0040004020100800u64), BB(0x8031050202000280u64), 59),
-    Magic::new(BB(0x0000020408102000u64), BB(0x242108084a490101u64), 59), Magic::new(BB(0x0000040810204000u64), BB(0x082080481a101000u64), 59), Magic::new(BB(0x00000a1020400000u64), BB(0x4000004a00902042u64), 59), Magic::new(BB(0x0000142240000000u64), BB(0x0100218108480800u64), 59), Magic::new(BB(0x0000284402000000u64), BB(0x0c28201202020840u64), 59), Magic::new(BB(0x0000500804020000u64), BB(0x0c28201202020840u64), 59), Magic::new(BB(0x0000201008040200u64), BB(0x0424040408020088u64), 59), Magic::new(BB(0x0000402010080400u64), BB(0x3209080800504480u64), 59),
-    Magic::new(BB(0x0002040810204000u64), BB(0x200202b108080400u64), 58), Magic::new(BB(0x0004081020400000u64), BB(0x200202b108080400u64), 59), Magic::new(BB(0x000a102040000000u64), BB(0x0034c00448441005u64), 59), Magic::new(BB(0x0014224000000000u64), BB(0x0000810850208802u64), 59), Magic::new(BB(0x0028440200000000u64), BB(0x02000808403d0101u64), 59), Magic::new(BB(0x0050080402000000u64), BB(0x0000801006900108u64), 59), Magic::new(BB(0x0020100804020000u64), BB(0x0000044448020400u64), 59), Magic::new(BB(0x0040201008040200u64), BB(0x0040440812022420u64), 58),
-];
+pub const BISHOP_MAGICS: [Magic; 64] = with_offsets([
+    Magic::new(BB(0x0040201008040200u64), BB(0x0208010404040224u64), 58), Magic::new(BB(0x0000402010080400u64), BB(0x00314102008a0000u64), 59), Magic::new(BB(0x0000004020100a00u64), BB(0xc1b001020a340a00u64), 59), Magic::new(BB(0x0000000040221400u64), BB(0x0004410021a40020u64), 59), Magic::new(BB(0x0000000002442800u64), BB(0x8012021080400804u64), 59), Magic::new(BB(0x0000000204085000u64), BB(0x2103100884020002u64), 59), Magic::new(BB(0x0000020408102000u64), BB(0x2186081404450041u64), 59), Magic::new(BB(0x0002040810204000u64), BB(0x0109804800a42000u64), 58),
+    Magic::new(BB(0x0020100804020000u64), BB(0x041028421c040412u64), 59), Magic::new(BB(0x0040201008040000u64), BB(0x000418261aca0200u64), 59), Magic::new(BB(0x00004020100a0000u64), BB(0x15801088a0810000u64), 59), Magic::new(BB(0x0000004022140000u64), BB(0x0109ac4101a204c2u64), 59), Magic::new(BB(0x0000000244280000u64), BB(0xa8300404200a0000u64), 59), Magic::new(BB(0x0000020408500000u64), BB(0x0080511042100000u64), 59), Magic::new(BB(0x0002040810200000u64), BB(0x10008c008c112800u64), 59), Magic::new(BB(0x0004081020400000u64), BB(0x8001410400c3850au64), 59),
+    Magic::new(BB(0x0010080402000200u64), BB(0x8010000690020804u64), 59), Magic::new(BB(0x0020100804000400u64), BB(0x0408009022008405u64), 59), Magic::new(BB(0x004020100a000a00u64), BB(0x0008024043850011u64), 57), Magic::new(BB(0x0000402214001400u64), BB(0x8404200804210148u64), 57), Magic::new(BB(0x0000024428002800u64), BB(0x0002000412020000u64), 57), Magic::new(BB(0x0002040850005000u64), BB(0x0802000101008222u64), 57), Magic::new(BB(0x0004081020002000u64), BB(0x20010a4200902400u64), 59), Magic::new(BB(0x0008102040004000u64), BB(0x0422000044540404u64), 59),
+    Magic::new(BB(0x0008040200020400u64), BB(0x1108048488101040u64), 59), Magic::new(BB(0x0010080400040800u64), BB(0x0050240808886091u64), 59), Magic::new(BB(0x0020100a000a1000u64), BB(0x1408020011120208u64), 57), Magic::new(BB(0x0040221400142200u64), BB(0x0058080010820002u64), 55), Magic::new(BB(0x0002442800284400u64), BB(0x02a1001103004004u64), 55), Magic::new(BB(0x0004085000500800u64), BB(0x8090020841008e00u64), 57), Magic::new(BB(0x0008102000201000u64), BB(0x200400a0004a1000u64), 59), Magic::new(BB(0x0010204000402000u64), BB(0x0000420144808400u64), 59),
+    Magic::new(BB(0x0004020002040800u64), BB(0x004823080810a010u64), 59), Magic::new(BB(0x0008040004081000u64), BB(0x0044042200210200u64), 59), Magic::new(BB(0x00100a000a102000u64), BB(0x000b040100021806u64), 57), Magic::new(BB(0x0022140014224000u64), BB(0x00a2004041040101u64), 55), Magic::new(BB(0x0044280028440200u64), BB(0xa190010410020200u64), 55), Magic::new(BB(0x0008500050080400u64), BB(0x08020401c1080808u64), 57), Magic::new(BB(0x0010200020100800u64), BB(0x0a040102020c0090u64), 59), Magic::new(BB(0x0020400040201000u64), BB(0x8401041100088843u64), 59),
+    Magic::new(BB(0x0002000204081000u64), BB(0x130804100a004421u64), 59), Magic::new(BB(0x0004000408102000u64), BB(0x00d2180208000240u64), 59), Magic::new(BB(0x000a000a10204000u64), BB(0x8162001402020400u64), 57), Magic::new(BB(0x0014001422400000u64), BB(0x00200c2214000806u64), 57), Magic::new(BB(0x0028002844020000u64), BB(0x0120080100440404u64), 57), Magic::new(BB(0x0050005008040200u64), BB(0x000925010a002502u64), 57), Magic::new(BB(0x0020002010080400u64), BB(0x008a0c03040c0600u64), 59), Magic::new(BB(0x0040004020100800u64), BB(0x0401024096008101u64), 59),
+    Magic::new(BB(0x0000020408102000u64), BB(0x9902090402420488u64), 59), Magic::new(BB(0x0000040810204000u64), BB(0x9004210402202000u64), 59), Magic::new(BB(0x00000a1020400000u64), BB(0x0834020082210000u64), 59), Magic::new(BB(0x0000142240000000u64), BB(0x0100090642022500u64), 59), Magic::new(BB(0x0000284402000000u64), BB(0x50c0001002020804u64), 59), Magic::new(BB(0x0000500804020000u64), BB(0x03424a9049020000u64), 59), Magic::new(BB(0x0000201008040200u64), BB(0x2005083004008402u64), 59), Magic::new(BB(0x0000402010080400u64), BB(0x008802008401080cu64), 59),
+    Magic::new(BB(0x0002040810204000u64), BB(0x0000820041444000u64), 58), Magic::new(BB(0x0004081020400000u64), BB(0x4470024108280201u64), 59), Magic::new(BB(0x000a102040000000u64), BB(0x004084804c040400u64), 59), Magic::new(BB(0x0014224000000000u64), BB(0x0040800100460801u64), 59), Magic::new(BB(0x0028440200000000u64), BB(0x0040240008830400u64), 59), Magic::new(BB(0x0050080402000000u64), BB(0x2020000888100420u64), 59), Magic::new(BB(0x0020100804020000u64), BB(0x4a402820a5860200u64), 59), Magic::new(BB(0x0040201008040200u64), BB(0x0084011004010041u64), 58),
+], ROOK_MAGICS[63].offset() + ROOK_MAGICS[63].table_size());
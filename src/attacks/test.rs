@@ -0,0 +1,139 @@
+#[cfg(test)]
+use super::finder::{DEFAULT_SEED, Slider, SquareSearch, precomputed_rs, relevant_mask};
+use super::magic::pext;
+use super::precomputed::{BISHOP_MAGICS, ROOK_MAGICS};
+use super::sliding::{SLIDING_ATTACKS_SIZE, bishop_moves, rook_moves};
+use crate::types::{BB, Piece, Square};
+
+// Deterministic occupancies, sparse and dense
+fn occupancies() -> impl Iterator<Item = u64> {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    (0..2000).map(move |i| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+
+        match i % 3 {
+            0 => seed,
+            1 => seed & seed.rotate_left(17),
+            _ => seed & seed.rotate_left(17) & seed.rotate_left(31),
+        }
+    })
+}
+
+// Whatever backend is compiled in, magics or PEXT, against walking the rays one square at a time
+#[test]
+fn test_sliding_attacks_match_rays() {
+    super::init();
+
+    for square in 0..64 {
+        let square = unsafe { Square::from_int(square) };
+
+        for occupied in occupancies().map(BB) {
+            assert_eq!(
+                Piece::WBishop.attacks(square, occupied),
+                bishop_moves(square, occupied),
+                "bishop on {square}, occupied {occupied:?}"
+            );
+            assert_eq!(
+                Piece::BRook.attacks(square, occupied),
+                rook_moves(square, occupied),
+                "rook on {square}, occupied {occupied:?}"
+            );
+            assert_eq!(
+                Piece::WQueen.attacks(square, occupied),
+                bishop_moves(square, occupied) | rook_moves(square, occupied)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_table_layout() {
+    // Every square gets its own slice, the rooks first and the bishops right after
+    let magics = ROOK_MAGICS.iter().chain(&BISHOP_MAGICS).collect::<Vec<_>>();
+    assert_eq!(magics[0].offset(), 0);
+    for pair in magics.windows(2) {
+        assert_eq!(pair[0].offset() + pair[0].table_size(), pair[1].offset());
+    }
+
+    assert_eq!(SLIDING_ATTACKS_SIZE, 102_400 + 5_248);
+}
+
+#[test]
+fn test_software_pext() {
+    assert_eq!(pext(0, 0), 0);
+    assert_eq!(pext(u64::MAX, 0xf0f0), 0xff);
+    assert_eq!(pext(0b1010_0110, 0b1111_0000), 0b1010);
+    assert_eq!(pext(0x8000_0000_0000_0001, 0x8000_0000_0000_0001), 0b11);
+
+    #[cfg(target_arch = "x86_64")]
+    if std::arch::is_x86_feature_detected!("bmi2") {
+        for (x, mask) in occupancies().zip(occupancies().skip(1)) {
+            let hardware = unsafe { bmi2_pext(x, mask) };
+            assert_eq!(pext(x, mask), hardware);
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+unsafe fn bmi2_pext(x: u64, mask: u64) -> u64 {
+    std::arch::x86_64::_pext_u64(x, mask)
+}
+
+#[test]
+fn test_relevant_masks() {
+    for square in 0..64 {
+        assert_eq!(
+            relevant_mask(Slider::Rook, unsafe { Square::from_int(square) }),
+            ROOK_MAGICS[square as usize].mask
+        );
+        assert_eq!(
+            relevant_mask(Slider::Bishop, unsafe { Square::from_int(square) }),
+            BISHOP_MAGICS[square as usize].mask
+        );
+    }
+}
+
+#[test]
+fn test_is_magic() {
+    let mut search = SquareSearch::new(Slider::Rook, Square::A1);
+    assert!(search.is_magic(0x0180_0088_e011_4000, 52));
+    assert!(!search.is_magic(0x0180_0088_e011_4000, 53));
+    assert!(!search.is_magic(0, 52));
+
+    let mut seed = 42;
+    let mut search = SquareSearch::new(Slider::Bishop, Square::D4);
+    let magic = search.find(55, &mut seed, u32::MAX).unwrap();
+    assert!(search.is_magic(magic, 55));
+}
+
+// The committed tables are exactly what the finder comes up with
+#[test]
+fn test_precomputed_is_generated() {
+    assert_eq!(
+        precomputed_rs(DEFAULT_SEED, false),
+        include_str!("precomputed.rs")
+    );
+}
+
+// Threads racing to call `init` first all get to see the whole table
+#[test]
+fn test_init_from_threads() {
+    let threads = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                super::init();
+                Piece::WQueen.attacks(Square::D4, BB(0))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        assert_eq!(
+            thread.join().unwrap(),
+            bishop_moves(Square::D4, BB(0)) | rook_moves(Square::D4, BB(0))
+        );
+    }
+}
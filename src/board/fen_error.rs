@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::fmt;
+
+// Every variant carries the byte offset into the FEN where parsing failed, and the character
+// found there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenError {
+    InvalidPiece { offset: usize, found: char },
+    TooManySquaresOnRank { offset: usize, found: char },
+    NotEnoughSquaresOnRank { offset: usize, found: char },
+    TooManyRanks { offset: usize, found: char },
+    TooFewRanks { offset: usize, found: char },
+    InvalidSideToMove { offset: usize, found: char },
+    InvalidCastlingRights { offset: usize, found: char },
+    InvalidEnPassantSquare { offset: usize, found: char },
+    InvalidHalfmoveClock { offset: usize, found: char },
+    InvalidFullmoveNumber { offset: usize, found: char },
+    TooManyFields { offset: usize, found: char },
+}
+
+impl FenError {
+    pub const fn offset(self) -> usize {
+        match self {
+            Self::InvalidPiece { offset, .. }
+            | Self::TooManySquaresOnRank { offset, .. }
+            | Self::NotEnoughSquaresOnRank { offset, .. }
+            | Self::TooManyRanks { offset, .. }
+            | Self::TooFewRanks { offset, .. }
+            | Self::InvalidSideToMove { offset, .. }
+            | Self::InvalidCastlingRights { offset, .. }
+            | Self::InvalidEnPassantSquare { offset, .. }
+            | Self::InvalidHalfmoveClock { offset, .. }
+            | Self::InvalidFullmoveNumber { offset, .. }
+            | Self::TooManyFields { offset, .. } => offset,
+        }
+    }
+
+    pub const fn found(self) -> char {
+        match self {
+            Self::InvalidPiece { found, .. }
+            | Self::TooManySquaresOnRank { found, .. }
+            | Self::NotEnoughSquaresOnRank { found, .. }
+            | Self::TooManyRanks { found, .. }
+            | Self::TooFewRanks { found, .. }
+            | Self::InvalidSideToMove { found, .. }
+            | Self::InvalidCastlingRights { found, .. }
+            | Self::InvalidEnPassantSquare { found, .. }
+            | Self::InvalidHalfmoveClock { found, .. }
+            | Self::InvalidFullmoveNumber { found, .. }
+            | Self::TooManyFields { found, .. } => found,
+        }
+    }
+
+    const fn message(self) -> &'static str {
+        match self {
+            Self::InvalidPiece { .. } => "Invalid piece",
+            Self::TooManySquaresOnRank { .. } => "Too many squares on rank",
+            Self::NotEnoughSquaresOnRank { .. } => "Not enough squares covered on rank",
+            Self::TooManyRanks { .. } => "Too many ranks",
+            Self::TooFewRanks { .. } => "Too few ranks",
+            Self::InvalidSideToMove { .. } => "Invalid side to move",
+            Self::InvalidCastlingRights { .. } => "Invalid castling rights",
+            Self::InvalidEnPassantSquare { .. } => "Invalid en passant square",
+            Self::InvalidHalfmoveClock { .. } => "Invalid halfmove clock",
+            Self::InvalidFullmoveNumber { .. } => "Invalid fullmove number",
+            Self::TooManyFields { .. } => "Invalid FEN, too many fields",
+        }
+    }
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at byte {}: '{}'",
+            self.message(),
+            self.offset(),
+            self.found()
+        )
+    }
+}
+
+impl Error for FenError {}
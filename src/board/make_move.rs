@@ -0,0 +1,192 @@
+use super::zobrist::{castling_key, ep_key, piece_square_key, side_key};
+use super::{Board, BoardState};
+use crate::movegen::Move;
+use crate::types::{CastlingRights, Color, Direction, Piece, Square};
+
+// Castling rights that survive a piece moving from or to the given square
+const CASTLING_RIGHTS_MASK: [u8; 64] = {
+    let mut array = [CastlingRights::AllSidesAll.as_int(); 64];
+
+    array[Square::A1 as usize] &= !CastlingRights::WhiteOOO.as_int();
+    array[Square::E1 as usize] &= !CastlingRights::WhiteAll.as_int();
+    array[Square::H1 as usize] &= !CastlingRights::WhiteOO.as_int();
+    array[Square::A8 as usize] &= !CastlingRights::BlackOOO.as_int();
+    array[Square::E8 as usize] &= !CastlingRights::BlackAll.as_int();
+    array[Square::H8 as usize] &= !CastlingRights::BlackOO.as_int();
+
+    array
+};
+
+// Where the rook starts and ends when the king castles to `to`
+pub(crate) const fn castling_rook_squares(to: Square) -> (Square, Square) {
+    match to {
+        Square::G1 => (Square::H1, Square::F1),
+        Square::C1 => (Square::A1, Square::D1),
+        Square::G8 => (Square::H8, Square::F8),
+        Square::C8 => (Square::A8, Square::D8),
+
+        _ => unreachable!(),
+    }
+}
+
+impl Board {
+    #[inline]
+    fn put_piece(&mut self, piece: Piece, square: Square) {
+        let bb = square.to_bitboard();
+
+        self.mailbox[square.as_int() as usize] = piece;
+        self.piece_bb[piece.to_index()] |= bb;
+        self.color_bb[piece.color().index()] |= bb;
+        self.piece_count[piece.to_index()] += 1;
+    }
+
+    #[inline]
+    fn remove_piece(&mut self, square: Square) -> Piece {
+        let bb = square.to_bitboard();
+        let piece = self.mailbox[square.as_int() as usize];
+
+        self.mailbox[square.as_int() as usize] = Piece::None;
+        self.piece_bb[piece.to_index()] ^= bb;
+        self.color_bb[piece.color().index()] ^= bb;
+        self.piece_count[piece.to_index()] -= 1;
+
+        piece
+    }
+
+    #[inline]
+    fn move_piece(&mut self, from: Square, to: Square) {
+        let piece = self.remove_piece(from);
+        self.put_piece(piece, to);
+    }
+
+    // The square the captured piece stands on. Only differs from `to` for en passant, where
+    // `ep_square` is the en passant square of the position the move is played in.
+    #[inline]
+    pub(crate) fn captured_square(m: Move, ep_square: Square) -> Square {
+        if m.to == ep_square && m.moved.type_of_to_piece() == Piece::WPawn {
+            let down = if m.moved.color() == Color::WHITE {
+                Direction::South
+            } else {
+                Direction::North
+            };
+
+            m.to.to_bitboard().shift_by_direction(down).lsb()
+        } else {
+            m.to
+        }
+    }
+
+    // Only set the en passant square if an enemy pawn can actually take on it, otherwise
+    // positions that are the same would look different.
+    #[inline]
+    fn en_passant_square_after(&self, m: Move) -> Square {
+        if m.moved.type_of_to_piece() != Piece::WPawn
+            || m.from.as_int().abs_diff(m.to.as_int()) != 16
+        {
+            return Square::None;
+        }
+
+        let ep = unsafe { Square::from_int(u8::midpoint(m.from.as_int(), m.to.as_int())) };
+        let them = !m.moved.color();
+        let pawns = self.piece_bb[Piece::WPawn.with_color(them).to_index()];
+
+        if (Piece::WPawn.with_color(m.moved.color()).attacks(ep, pawns) & pawns).is_empty() {
+            Square::None
+        } else {
+            ep
+        }
+    }
+
+    #[allow(dead_code, clippy::trivially_copy_pass_by_ref)]
+    pub fn make_move(&mut self, m: &Move) {
+        let is_pawn = m.moved.type_of_to_piece() == Piece::WPawn;
+        let castling_rights = self.state.castling_rights
+            & CASTLING_RIGHTS_MASK[m.from.as_int() as usize]
+            & CASTLING_RIGHTS_MASK[m.to.as_int() as usize];
+        let ep_square = self.en_passant_square_after(*m);
+
+        let mut hash = self.state.hash
+            ^ side_key()
+            ^ self.ep_hash()
+            ^ castling_key(self.state.castling_rights)
+            ^ castling_key(castling_rights);
+        if ep_square != Square::None {
+            hash ^= ep_key(ep_square);
+        }
+
+        let mut pawn_key = self.state.pawn_key;
+        if m.captured != Piece::None {
+            let square = Self::captured_square(*m, self.state.ep_square);
+            hash ^= piece_square_key(m.captured, square);
+            if m.captured.type_of_to_piece() == Piece::WPawn {
+                pawn_key ^= piece_square_key(m.captured, square);
+            }
+            self.remove_piece(square);
+        }
+
+        let placed = m.promotion.unwrap_or(m.moved);
+        hash ^= piece_square_key(m.moved, m.from) ^ piece_square_key(placed, m.to);
+        if is_pawn {
+            pawn_key ^= piece_square_key(m.moved, m.from);
+            if m.promotion.is_none() {
+                pawn_key ^= piece_square_key(m.moved, m.to);
+            }
+        }
+        self.remove_piece(m.from);
+        self.put_piece(placed, m.to);
+
+        if m.is_castling {
+            let (rook_from, rook_to) = castling_rook_squares(m.to);
+            let rook = Piece::WRook.with_color(m.moved.color());
+            hash ^= piece_square_key(rook, rook_from) ^ piece_square_key(rook, rook_to);
+            self.move_piece(rook_from, rook_to);
+        }
+
+        let state = Box::new(BoardState {
+            castling_rights,
+            rule_50: if is_pawn || m.captured != Piece::None {
+                0
+            } else {
+                self.state.rule_50.saturating_add(1)
+            },
+            ep_square,
+            hash,
+            pawn_key,
+            last_move: *m,
+            ..Default::default()
+        });
+
+        let previous = std::mem::replace(&mut self.state, state);
+        self.state.previous = Some(previous);
+        self.side_to_move = !self.side_to_move;
+        self.game_ply += 1;
+
+        self.populate_state();
+    }
+
+    #[allow(dead_code)]
+    pub fn unmake_move(&mut self) {
+        let m = self.state.last_move;
+        let previous = self
+            .state
+            .previous
+            .take()
+            .expect("unmake_move called without a move to unmake");
+
+        if m.is_castling {
+            let (rook_from, rook_to) = castling_rook_squares(m.to);
+            self.move_piece(rook_to, rook_from);
+        }
+
+        self.remove_piece(m.to);
+        self.put_piece(m.moved, m.from);
+
+        if m.captured != Piece::None {
+            self.put_piece(m.captured, Self::captured_square(m, previous.ep_square));
+        }
+
+        self.state = previous;
+        self.side_to_move = !self.side_to_move;
+        self.game_ply -= 1;
+    }
+}
@@ -1,11 +1,24 @@
 #![allow(clippy::cast_possible_truncation)]
 
+mod fen_error;
+mod make_move;
+mod see;
 #[cfg(test)]
 mod test;
+mod validate;
+mod zobrist;
 
-use std::fmt;
+use std::fmt::{self, Write as _};
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
+pub use fen_error::FenError;
+pub(crate) use make_move::castling_rook_squares;
+pub(crate) use see::SEE_VALUES;
+#[allow(unused_imports)]
+pub use validate::{PositionError, StrictFenError};
+
+use crate::movegen::Move;
 use crate::types::{Bitboard, CastlingRights, Color, Piece, Square};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
@@ -13,38 +26,59 @@ pub(crate) struct BoardState {
     pub(crate) castling_rights: u8,
     pub(crate) rule_50: u8,
     pub(crate) ep_square: Square,
+    pub(crate) hash: u64,
+    // Zobrist key of the pawns alone, for caching pawn structure evaluation
+    pub(crate) pawn_key: u64,
 
     pub(crate) attacks: [Bitboard; 2],
     pub(crate) checkers: Bitboard,
     pub(crate) previous: Option<Box<Self>>,
     pub(crate) king_blockers: [Bitboard; 2],
     pub(crate) pinners: [Bitboard; 2],
+
+    // The move that led from `previous` to this state, needed to unmake it
+    pub(crate) last_move: Move,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Board {
     pub(crate) mailbox: [Piece; 64],
     pub(crate) piece_bb: [Bitboard; Piece::N_PIECES],
     pub(crate) color_bb: [Bitboard; 2],
     pub(crate) piece_count: [u8; Piece::N_PIECES],
     pub(crate) side_to_move: Color,
+    // Half moves since the start of the game, fullmove number is derived from it
+    pub(crate) game_ply: u32,
 
     pub(crate) state: Box<BoardState>,
 }
 
 impl Default for Board {
     fn default() -> Self {
+        // Every other constructor comes through here or `new_starting`, no board can look up
+        // attacks before the table is there
+        crate::attacks::init();
+
         Self {
             mailbox: [Piece::None; 64],
             piece_bb: Default::default(),
             color_bb: Default::default(),
             piece_count: Default::default(),
             side_to_move: Color::default(),
+            game_ply: 0,
             state: Box::default(),
         }
     }
 }
 
+// The derived one would walk the whole `previous` chain, the Zobrist key already identifies the
+// position
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.state.hash.hash(state);
+    }
+}
+
 impl BoardState {
     #[inline]
     #[allow(dead_code)]
@@ -62,18 +96,35 @@ impl BoardState {
     }
 }
 
+// The character starting at byte `i`, parsing only stops on ASCII bytes or at the start of a
+// multi-byte character so this is always a char boundary
+fn char_at(s: &str, i: usize) -> char {
+    s[i..].chars().next().unwrap_or(' ')
+}
+
 impl FromStr for Board {
-    type Err = String;
+    type Err = FenError;
+
+    #[allow(clippy::too_many_lines)]
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        macro_rules! error {
+            ($kind:ident, $i:expr) => {
+                Err(FenError::$kind {
+                    offset: $i,
+                    found: char_at(input, $i),
+                })
+            };
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut self_ = Self::default();
 
-        let s = s.as_bytes();
+        let s = input.as_bytes();
         let mut i = 0;
 
         let mut rank = 8;
         let mut file = 1;
         let mut phase = 0;
+        let mut fullmove: u32 = 0;
 
         while i < s.len() {
             let c = s[i];
@@ -86,11 +137,15 @@ impl FromStr for Board {
 
             if phase == 1 {
                 if rank != 1 {
-                    return Err("Too few ranks".to_owned());
+                    return error!(TooFewRanks, i);
+                }
+
+                if file != 9 {
+                    return error!(NotEnoughSquaresOnRank, i);
                 }
 
                 if !matches!(c.to_ascii_lowercase(), b'w' | b'b') {
-                    return Err(format!("Invalid side to move '{}'", c as char));
+                    return error!(InvalidSideToMove, i);
                 }
 
                 self_.side_to_move = Color::from(c as char);
@@ -99,7 +154,7 @@ impl FromStr for Board {
                 continue;
             } else if phase == 2 {
                 if !matches!(c, b'K' | b'Q' | b'k' | b'q' | b'-') {
-                    return Err(format!("Invalid castling rights '{}'", c as char));
+                    return error!(InvalidCastlingRights, i);
                 }
 
                 self_.state.castling_rights |= CastlingRights::from(c as char).as_int();
@@ -110,43 +165,83 @@ impl FromStr for Board {
                 match c {
                     b'-' => {}
                     b'a'..=b'h' => {
-                        if i + 1 >= s.len() || !matches!(s[i + 1], b'1'..=b'8') {
-                            return Err("Invalid en passant square".to_owned());
+                        if i + 1 >= s.len() {
+                            return error!(InvalidEnPassantSquare, i);
+                        }
+                        if !matches!(s[i + 1], b'1'..=b'8') {
+                            return error!(InvalidEnPassantSquare, i + 1);
+                        }
+
+                        // Only a square a pawn of theirs could have just skipped over
+                        let rank = if self_.side_to_move == Color::WHITE {
+                            b'6'
+                        } else {
+                            b'3'
+                        };
+                        if s[i + 1] != rank {
+                            return error!(InvalidEnPassantSquare, i + 1);
                         }
 
                         self_.state.ep_square = unsafe {
                             Square::unsafe_from_str(&String::from_utf8_unchecked(vec![c, s[i + 1]]))
-                        }
+                        };
+                        i += 1;
                     }
 
-                    _ => return Err(format!("Did not expect charachter '{}'", c as char)),
+                    _ => return error!(InvalidEnPassantSquare, i),
                 }
 
                 i += 1;
                 continue;
             } else if phase == 4 {
                 if !c.is_ascii_digit() {
-                    return Err("Invalid Halfmove clock".to_owned());
+                    return error!(InvalidHalfmoveClock, i);
                 }
 
-                self_.state.rule_50 = self_.state.rule_50 * 10 + (c - b'0');
+                // Anything past 255 can't come from a real game
+                let Some(rule_50) = self_
+                    .state
+                    .rule_50
+                    .checked_mul(10)
+                    .and_then(|x| x.checked_add(c - b'0'))
+                else {
+                    return error!(InvalidHalfmoveClock, i);
+                };
+
+                self_.state.rule_50 = rule_50;
 
                 i += 1;
                 continue;
             } else if phase == 5 {
                 if !c.is_ascii_digit() {
-                    return Err("Invalid fullmove number".to_owned());
+                    return error!(InvalidFullmoveNumber, i);
                 }
 
+                // The game ply is twice the fullmove number, and has to keep fitting for as many
+                // moves as can still be played
+                let Some(next) = fullmove
+                    .checked_mul(10)
+                    .and_then(|x| x.checked_add(u32::from(c - b'0')))
+                    .filter(|&x| x <= u32::MAX / 4)
+                else {
+                    return error!(InvalidFullmoveNumber, i);
+                };
+
+                fullmove = next;
+
                 i += 1;
                 continue;
             } else if phase > 5 {
-                return Err("Invalid FEN, too many phases".to_owned());
+                return error!(TooManyFields, i);
             }
 
             match c {
                 b'P' | b'N' | b'B' | b'R' | b'Q' | b'K' | b'p' | b'n' | b'b' | b'r' | b'q'
                 | b'k' => {
+                    if file > 8 {
+                        return error!(TooManySquaresOnRank, i);
+                    }
+
                     let piece = Piece::from_char(c as char);
                     let square =
                         u32::from(Square::from_rank_file(rank as u8 - 1, file as u8 - 1).as_int());
@@ -158,32 +253,35 @@ impl FromStr for Board {
                     file += 1;
                 }
 
-                b'1'..=b'8' => {
+                b'1'..=b'9' => {
                     let n = c - b'0';
                     file += n as usize;
                     if file > 9 {
-                        return Err("Too many squares on rank".to_owned());
+                        return error!(TooManySquaresOnRank, i);
                     }
                 }
 
                 b'/' => {
                     if file != 9 {
-                        return Err("Not enough squares covered on rank".to_owned());
+                        return error!(NotEnoughSquaresOnRank, i);
                     }
 
                     if rank == 1 {
-                        return Err("Too many ranks".to_owned());
+                        return error!(TooManyRanks, i);
                     }
 
                     rank -= 1;
                     file = 1;
                 }
-                _ => return Err(format!("Invalid charachter '{}'", c as char)),
+                _ => return error!(InvalidPiece, i),
             }
 
             i += 1;
         }
 
+        self_.game_ply = 2 * (fullmove.max(1) - 1) + u32::from(self_.side_to_move.0);
+        self_.state.hash = self_.compute_hash();
+        self_.state.pawn_key = self_.compute_pawn_key();
         self_.populate_state();
         Ok(self_)
     }
@@ -196,6 +294,8 @@ impl Board {
         #[allow(clippy::enum_glob_use)]
         use crate::types::{BB, Piece::*};
 
+        crate::attacks::init();
+
         let mut x = Board {
             mailbox: [
                 WRook, WKnight, WBishop, WQueen, WKing, WBishop, WKnight, WRook, WPawn, WPawn,
@@ -222,9 +322,12 @@ impl Board {
             color_bb: [BB(0xffff), BB(0xffff_0000_0000_0000)],
             piece_count: [8, 2, 2, 2, 1, 1, 8, 2, 2, 2, 1, 1],
             side_to_move: Color::WHITE,
+            game_ply: 0,
             state: Box::new(BoardState::new_starting()),
         };
 
+        x.state.hash = x.compute_hash();
+        x.state.pawn_key = x.compute_pawn_key();
         x.populate_state();
         x
     }
@@ -236,6 +339,71 @@ impl Board {
     }
 }
 
+impl Board {
+    pub const fn fullmove(&self) -> u32 {
+        1 + self.game_ply / 2
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                let piece = self.mailbox[Square::from_rank_file(rank, file).as_int() as usize];
+                if piece == Piece::None {
+                    empty += 1;
+                    continue;
+                }
+
+                if empty != 0 {
+                    fen.push(char::from(b'0' + empty));
+                    empty = 0;
+                }
+                fen.push(piece.to_char());
+            }
+
+            if empty != 0 {
+                fen.push(char::from(b'0' + empty));
+            }
+            if rank != 0 {
+                fen.push('/');
+            }
+        }
+
+        fen.push_str(if self.side_to_move == Color::WHITE {
+            " w "
+        } else {
+            " b "
+        });
+
+        let castling = [
+            (CastlingRights::WhiteOO, 'K'),
+            (CastlingRights::WhiteOOO, 'Q'),
+            (CastlingRights::BlackOO, 'k'),
+            (CastlingRights::BlackOOO, 'q'),
+        ];
+        let len = fen.len();
+        for (right, c) in castling {
+            if self.state.castling_rights & right.as_int() != 0 {
+                fen.push(c);
+            }
+        }
+        if fen.len() == len {
+            fen.push('-');
+        }
+
+        if self.state.ep_square == Square::None {
+            fen.push_str(" -");
+        } else {
+            let _ = write!(fen, " {}", self.state.ep_square);
+        }
+
+        let _ = write!(fen, " {} {}", self.state.rule_50, self.fullmove());
+        fen
+    }
+}
+
 impl fmt::Display for Board {
     // Stolen from stockfish
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -255,6 +423,7 @@ impl fmt::Display for Board {
         }
 
         f.write_str("   a   b   c   d   e   f   g   h\n")?;
+        f.write_fmt(format_args!("\nfen: {}", self.to_fen()))?;
         f.write_fmt(format_args!(
             "\ncastling_rights: {}, rule_50: {}, ep_square: {:?}\n",
             self.state.castling_rights, self.state.rule_50, self.state.ep_square
@@ -0,0 +1,191 @@
+use super::Board;
+use crate::movegen::Move;
+use crate::search::Score;
+use crate::types::{Bitboard, Color, Piece, Square};
+
+// Material for exchanges and capture ordering, indexed by `Piece::to_index() % 6`. The king is
+// never actually taken, it just can't capture onto a defended square.
+pub const SEE_VALUES: [Score; 6] = [100, 300, 300, 500, 900, 0];
+
+const fn see_value_of(piece: Piece) -> Score {
+    SEE_VALUES[piece.to_index() % 6]
+}
+
+// Pieces in the order they join an exchange, cheapest first
+const EXCHANGE_ORDER: [Piece; 6] = [
+    Piece::WPawn,
+    Piece::WKnight,
+    Piece::WBishop,
+    Piece::WRook,
+    Piece::WQueen,
+    Piece::WKing,
+];
+
+// Where an exchange on `m.to` starts from: what `m` wins, the value of what it leaves on the
+// square and the occupancy after it
+struct Exchange {
+    gain: Score,
+    on_square: Score,
+    occupied: Bitboard,
+}
+
+impl Board {
+    // Sliders that can see `square` once the occupancy changed, behind a piece that just left
+    fn xray_attackers(&self, square: Square, occupied: Bitboard) -> Bitboard {
+        let bb = |piece: Piece| self.piece_bb[piece.to_index()];
+        let queens = bb(Piece::WQueen) | bb(Piece::BQueen);
+
+        (Piece::WBishop.attacks(square, occupied)
+            & (bb(Piece::WBishop) | bb(Piece::BBishop) | queens))
+            | (Piece::WRook.attacks(square, occupied)
+                & (bb(Piece::WRook) | bb(Piece::BRook) | queens))
+    }
+
+    // The cheapest of `attackers` that belongs to `color`
+    fn least_valuable_attacker(
+        &self,
+        attackers: Bitboard,
+        color: Color,
+    ) -> Option<(Piece, Square)> {
+        let ours = attackers & self.color_bb[color.index()];
+
+        EXCHANGE_ORDER.into_iter().find_map(|piece| {
+            let bb = ours & self.piece_bb[piece.with_color(color).to_index()];
+            (!bb.is_empty()).then(|| (piece, bb.lsb()))
+        })
+    }
+
+    fn start_exchange(&self, m: Move) -> Exchange {
+        let captured_square = Self::captured_square(m, self.state.ep_square);
+        let occupied = ((self.color_bb[0] | self.color_bb[1])
+            & !m.from.to_bitboard()
+            & !captured_square.to_bitboard())
+            | m.to.to_bitboard();
+
+        let mut gain = if m.captured == Piece::None {
+            0
+        } else {
+            see_value_of(m.captured)
+        };
+        let on_square = match m.promotion {
+            Some(promotion) => {
+                gain += see_value_of(promotion) - SEE_VALUES[0];
+                see_value_of(promotion)
+            }
+            None => see_value_of(m.moved),
+        };
+
+        Exchange {
+            gain,
+            on_square,
+            occupied,
+        }
+    }
+
+    // Material won by `m` if both sides keep recapturing on its target square with their
+    // cheapest piece, as long as that is good for them. Pins are not taken into account.
+    #[allow(dead_code, clippy::trivially_copy_pass_by_ref)]
+    pub fn see_value(&self, m: &Move) -> Score {
+        if m.is_castling {
+            return 0;
+        }
+
+        let Exchange {
+            gain: first,
+            mut on_square,
+            mut occupied,
+        } = self.start_exchange(*m);
+
+        // gains[i] is what the side making the i-th capture is up if the exchange stops there.
+        // Every capture takes a piece off the board, so there can't be more than 64.
+        let mut gains = [0; 64];
+        gains[0] = first;
+        let mut depth = 0;
+
+        let mut side = !m.moved.color();
+        let mut attackers = self.attackers_to(m.to, occupied) & occupied;
+        while let Some((piece, square)) = self.least_valuable_attacker(attackers, side) {
+            // The king can only take last
+            if piece == Piece::WKing && !(attackers & self.color_bb[(!side).index()]).is_empty() {
+                break;
+            }
+
+            depth += 1;
+            gains[depth] = on_square - gains[depth - 1];
+            on_square = SEE_VALUES[piece.to_index()];
+
+            occupied ^= square.to_bitboard();
+            attackers = (attackers | self.xray_attackers(m.to, occupied)) & occupied;
+            side = !side;
+        }
+
+        // Either side can stop recapturing when that is better for them
+        while depth > 0 {
+            gains[depth - 1] = -(-gains[depth - 1]).max(gains[depth]);
+            depth -= 1;
+        }
+
+        gains[0]
+    }
+
+    // Whether `m` wins at least `threshold` in the exchange `see_value` plays out, without
+    // playing out more of it than needed
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn see(&self, m: &Move, threshold: Score) -> bool {
+        if m.is_castling {
+            return threshold <= 0;
+        }
+
+        let Exchange {
+            gain,
+            on_square,
+            mut occupied,
+        } = self.start_exchange(*m);
+
+        // What we are up over the threshold if they don't recapture
+        let mut balance = gain - threshold;
+        if balance < 0 {
+            return false;
+        }
+
+        // Same, if they take and we have nothing else to throw in
+        balance = on_square - balance;
+        if balance <= 0 {
+            return true;
+        }
+
+        let mut side = m.moved.color();
+        let mut attackers = self.attackers_to(m.to, occupied);
+        // 1 while the side that made `m` is at the threshold after the captures so far
+        let mut result = 1;
+        loop {
+            side = !side;
+            attackers &= occupied;
+            let Some((piece, square)) = self.least_valuable_attacker(attackers, side) else {
+                break;
+            };
+
+            result ^= 1;
+
+            // Taking with the king only works if nothing can take back
+            if piece == Piece::WKing {
+                if !(attackers & self.color_bb[(!side).index()]).is_empty() {
+                    result ^= 1;
+                }
+                break;
+            }
+
+            // What the side that just captured is up if it loses this piece too. If that is
+            // still enough for it, the other side can't do better by going on.
+            balance = SEE_VALUES[piece.to_index()] - balance;
+            if balance < result {
+                break;
+            }
+
+            occupied ^= square.to_bitboard();
+            attackers |= self.xray_attackers(m.to, occupied);
+        }
+
+        result == 1
+    }
+}
@@ -11,11 +11,13 @@ fn expect_parse_ok(fen: &str) -> Board {
     board.unwrap()
 }
 
-fn expect_parse_err(fen: &str) {
+fn expect_parse_err(fen: &str) -> FenError {
     let board = Board::from_str(fen);
 
     println!("{:?}", board.clone().err());
     assert!(board.is_err(), "Should fail to parse: {fen}");
+
+    board.unwrap_err()
 }
 
 #[test]
@@ -32,38 +34,105 @@ fn test_valid_custom_position() {
 
 #[test]
 fn test_invalid_piece_in_castling_rights() {
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQa - 0 1");
+    assert_eq!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQa - 0 1"),
+        FenError::InvalidCastlingRights {
+            offset: 48,
+            found: 'a'
+        }
+    );
 }
 
 #[test]
 fn test_invalid_rank_overflow() {
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/8/8/8 w - - 0 1"); // too many ranks
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/8/8/8 w - - 0 1"),
+        FenError::TooManyRanks { found: '/', .. }
+    ));
 }
 
 #[test]
 fn test_invalid_rank_underflow() {
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/8 w - - 0 1"); // too few ranks
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/8 w - - 0 1"),
+        FenError::TooFewRanks { found: 'w', .. }
+    ));
 }
 
 #[test]
 fn test_too_many_squares_in_rank() {
-    expect_parse_err("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1");
+    assert_eq!(
+        expect_parse_err("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1"),
+        FenError::TooManySquaresOnRank {
+            offset: 18,
+            found: '9'
+        }
+    );
+    assert!(matches!(
+        expect_parse_err("rnbqkbnrp/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1"),
+        FenError::TooManySquaresOnRank { offset: 8, .. }
+    ));
+}
+
+#[test]
+fn test_not_enough_squares_in_rank() {
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1"),
+        FenError::NotEnoughSquaresOnRank { offset: 16, .. }
+    ));
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN w - - 0 1"),
+        FenError::NotEnoughSquaresOnRank { found: 'w', .. }
+    ));
 }
 
 #[test]
 fn test_en_passant_invalid_format() {
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - a 0 1"); // missing number
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - a9 0 1"); // invalid square
+    // missing number
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - a 0 1"),
+        FenError::InvalidEnPassantSquare { found: ' ', .. }
+    ));
+    // invalid square
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - a9 0 1"),
+        FenError::InvalidEnPassantSquare { found: '9', .. }
+    ));
+    // not on the side to move's sixth rank
+    for fen in [
+        "4k3/8/8/4p3/8/8/8/4K3 w - e3 0 1",
+        "4k3/8/8/8/8/8/8/4K3 w - a1 0 1",
+        "4k3/8/8/8/8/8/8/4K3 b - h8 0 1",
+        "4k3/8/8/8/8/8/3P4/4K3 w - e3 0 1",
+    ] {
+        assert!(matches!(
+            expect_parse_err(fen),
+            FenError::InvalidEnPassantSquare { .. }
+        ));
+    }
 }
 
 #[test]
 fn test_halfmove_non_numeric() {
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - x 1");
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - x 1"),
+        FenError::InvalidHalfmoveClock { found: 'x', .. }
+    ));
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 256 1"),
+        FenError::InvalidHalfmoveClock { found: '6', .. }
+    ));
 }
 
 #[test]
 fn test_extra_characters() {
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1 EXTRA");
+    assert!(matches!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1 EXTRA"),
+        FenError::TooManyFields {
+            offset: 54,
+            found: 'E'
+        }
+    ));
 }
 
 #[test]
@@ -74,5 +143,464 @@ fn test_empty_board() {
 
 #[test]
 fn test_invalid_characters_in_piece_placement() {
-    expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQXBNR w KQkq - 0 1");
+    assert_eq!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQXBNR w KQkq - 0 1"),
+        FenError::InvalidPiece {
+            offset: 39,
+            found: 'X'
+        }
+    );
+    assert_eq!(
+        expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ\u{265a}BNR w KQkq - 0 1"),
+        FenError::InvalidPiece {
+            offset: 39,
+            found: '\u{265a}'
+        }
+    );
+}
+
+#[test]
+fn test_fen_error_display() {
+    let e = expect_parse_err("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x - - 0 1");
+
+    assert_eq!(e.to_string(), "Invalid side to move at byte 44: 'x'");
+}
+
+fn assert_same_position(board: &Board, fen: &str) {
+    let expected = expect_parse_ok(fen);
+
+    assert_eq!(board.mailbox, expected.mailbox);
+    assert_eq!(board.piece_bb, expected.piece_bb);
+    assert_eq!(board.color_bb, expected.color_bb);
+    assert_eq!(board.piece_count, expected.piece_count);
+    assert_eq!(board.side_to_move, expected.side_to_move);
+    assert_eq!(board.state.castling_rights, expected.state.castling_rights);
+    assert_eq!(board.state.ep_square, expected.state.ep_square);
+    assert_eq!(board.state.rule_50, expected.state.rule_50);
+    assert_eq!(board.state.checkers, expected.state.checkers);
+}
+
+fn play(board: &mut Board, uci: &str) {
+    let m = board
+        .parse_uci_move(uci)
+        .unwrap_or_else(|| panic!("{uci} should be legal"));
+
+    board.make_move(&m);
+}
+
+#[test]
+fn test_make_unmake_round_trip() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "8/8/8/3k4/2pP4/8/8/4K3 b - d3 0 1",
+    ] {
+        let mut board = expect_parse_ok(fen);
+        let original = board.clone();
+
+        for m in board.generate_moves() {
+            board.make_move(&m);
+            board.unmake_move();
+
+            assert_eq!(board, original, "{m} did not unmake cleanly in {fen}");
+        }
+    }
+}
+
+#[test]
+fn test_make_move_updates_state() {
+    let mut board = Board::new_starting();
+
+    play(&mut board, "e2e4");
+    assert_same_position(
+        &board,
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+    );
+
+    play(&mut board, "g8f6");
+    play(&mut board, "e4e5");
+    play(&mut board, "d7d5");
+    assert_same_position(
+        &board,
+        "rnbqkb1r/ppp1pppp/5n2/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3",
+    );
+
+    play(&mut board, "e5d6");
+    assert_same_position(
+        &board,
+        "rnbqkb1r/ppp1pppp/3P1n2/8/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3",
+    );
+
+    play(&mut board, "e7e6");
+    play(&mut board, "f1e2");
+    play(&mut board, "f8e7");
+    play(&mut board, "g1f3");
+    play(&mut board, "e8g8");
+    assert_same_position(
+        &board,
+        "rnbq1rk1/ppp1bppp/3Ppn2/8/8/5N2/PPPPBPPP/RNBQK2R w KQ - 4 6",
+    );
+
+    play(&mut board, "h1g1");
+    assert_same_position(
+        &board,
+        "rnbq1rk1/ppp1bppp/3Ppn2/8/8/5N2/PPPPBPPP/RNBQK1R1 b Q - 5 6",
+    );
+}
+
+fn assert_hash_consistent(board: &mut Board, depth: u32) {
+    assert_eq!(
+        Board::hash(board),
+        board.compute_hash(),
+        "Hash drifted at\n{board}"
+    );
+    assert_eq!(
+        board.state.pawn_key,
+        board.compute_pawn_key(),
+        "Pawn key drifted at\n{board}"
+    );
+    if depth == 0 {
+        return;
+    }
+
+    let hash = Board::hash(board);
+    for m in board.generate_moves() {
+        board.make_move(&m);
+        assert_hash_consistent(board, depth - 1);
+        board.unmake_move();
+
+        assert_eq!(Board::hash(board), hash);
+    }
+}
+
+#[test]
+fn test_incremental_hash() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ] {
+        assert_hash_consistent(&mut expect_parse_ok(fen), 3);
+    }
+}
+
+#[test]
+fn test_hash_transpositions() {
+    let start = Board::new_starting();
+    let mut board = start.clone();
+
+    for m in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+        play(&mut board, m);
+    }
+    assert_eq!(board.hash(), start.hash());
+
+    // e3 can't be taken, so it must not change the key
+    play(&mut board, "e2e4");
+    let parsed = expect_parse_ok("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    assert_eq!(board.hash(), parsed.hash());
+
+    // But d6 can
+    let mut board = expect_parse_ok("rnbqkbnr/pppppppp/8/4P3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2");
+    play(&mut board, "d7d5");
+
+    let with_ep = expect_parse_ok("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3");
+    let without_ep = expect_parse_ok("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3");
+    assert_eq!(board.hash(), with_ep.hash());
+    assert_ne!(board.hash(), without_ep.hash());
+}
+
+#[test]
+fn test_fen_round_trip() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r1bqkbnr/pppppppp/n7/8/8/8/PPPPPPPP/RNBQKBNR b KQ - 12 34",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkb1r/ppp1pppp/5n2/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3",
+        "8/8/8/3k4/2pP4/8/8/4K3 b - d3 0 57",
+        "4k3/8/8/8/8/8/8/4K2R w K - 99 150",
+        "8/8/8/8/8/8/8/8 w - - 0 1",
+    ] {
+        assert_eq!(expect_parse_ok(fen).to_fen(), fen);
+    }
+}
+
+#[test]
+fn test_fullmove_counter() {
+    let mut board = Board::new_starting();
+    assert_eq!(board.fullmove(), 1);
+
+    play(&mut board, "e2e4");
+    assert_eq!(board.fullmove(), 1);
+    play(&mut board, "e7e5");
+    assert_eq!(board.fullmove(), 2);
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+    );
+
+    board.unmake_move();
+    assert_eq!(board.fullmove(), 1);
+
+    // Castling rights come out in canonical order
+    let board = expect_parse_ok("r3k2r/8/8/8/8/8/8/R3K2R w qkQK - 0 1");
+    assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+}
+
+#[test]
+fn test_large_fullmove_number() {
+    // Twice these doesn't fit in 16 bits, and the last ones not even once
+    for fen in [
+        "8/8/8/4k3/8/8/8/4K3 w - - 0 40000",
+        "8/8/8/4k3/8/8/8/4K3 b - - 0 65535",
+        "8/8/8/4k3/8/8/8/4K3 w - - 0 70000",
+        "8/8/8/4k3/8/8/8/4K3 b - - 0 1073741823",
+    ] {
+        assert_eq!(expect_parse_ok(fen).to_fen(), fen);
+    }
+
+    // Too large for the game ply
+    assert!(matches!(
+        expect_parse_err("8/8/8/4k3/8/8/8/4K3 w - - 0 1073741824"),
+        FenError::InvalidFullmoveNumber { found: '4', .. }
+    ));
+    assert!(matches!(
+        expect_parse_err("8/8/8/4k3/8/8/8/4K3 w - - 0 4294967296"),
+        FenError::InvalidFullmoveNumber { found: '6', .. }
+    ));
+
+    let mut board = expect_parse_ok("8/8/8/4k3/8/8/8/4K3 b - - 0 65535");
+    play(&mut board, "e5e4");
+    assert_eq!(board.fullmove(), 65536);
+
+    let mut board = expect_parse_ok("8/8/8/4k3/8/8/8/4K3 b - - 0 1073741823");
+    play(&mut board, "e5e4");
+    assert_eq!(board.fullmove(), 1_073_741_824);
+}
+
+#[test]
+fn test_validate_legal_positions() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkb1r/ppp1pppp/5n2/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3",
+        "8/8/8/3k4/2pP4/8/8/4K3 b - d3 0 1",
+    ] {
+        assert_eq!(expect_parse_ok(fen).validate(), vec![], "{fen}");
+        assert!(Board::from_fen_strict(fen).is_ok());
+    }
+}
+
+#[test]
+fn test_validate_kings() {
+    assert_eq!(
+        expect_parse_ok("8/8/8/8/8/8/8/8 w - - 0 1").validate(),
+        vec![
+            PositionError::MissingKing(Color::WHITE),
+            PositionError::MissingKing(Color::BLACK)
+        ]
+    );
+    assert_eq!(
+        expect_parse_ok("4k3/8/8/8/8/8/8/K3K3 w - - 0 1").validate(),
+        vec![PositionError::TooManyKings(Color::WHITE)]
+    );
+}
+
+#[test]
+fn test_validate_pawns() {
+    assert_eq!(
+        expect_parse_ok("P3k3/8/8/8/8/8/8/4K2p w - - 0 1").validate(),
+        vec![
+            PositionError::PawnOnBackRank(Square::H1),
+            PositionError::PawnOnBackRank(Square::A8)
+        ]
+    );
+}
+
+#[test]
+fn test_validate_material() {
+    // Nine queens and eight pawns, or seventeen pieces
+    assert_eq!(
+        expect_parse_ok("QQQQQQQQ/Q7/8/8/8/8/PPPPPPPP/4K2k w - - 0 1").validate(),
+        vec![
+            PositionError::TooManyPieces(Color::WHITE),
+            PositionError::TooManyPromotions(Color::WHITE),
+        ]
+    );
+
+    // Three knights with all the pawns still there
+    assert_eq!(
+        expect_parse_ok("4k3/8/8/8/8/8/PPPPPPPP/1NN1K1N1 w - - 0 1").validate(),
+        vec![PositionError::TooManyPromotions(Color::WHITE)]
+    );
+
+    // Nine queens is fine once all the pawns are gone
+    let fen = "QQQQQQQQ/Q7/8/8/8/8/8/4K2k b - - 0 1";
+    assert_eq!(expect_parse_ok(fen).validate(), vec![]);
+
+    // The many-queens position that used to overflow the move list
+    let fen = "Q1QQQQQk/Q5QR/Q5Q1/1Q5Q/Q4Q1Q/Q6Q/Q6Q/KQQQQQQB w - - 0 1";
+    assert!(
+        expect_parse_ok(fen)
+            .validate()
+            .contains(&PositionError::TooManyPieces(Color::WHITE))
+    );
+}
+
+#[test]
+fn test_validate_castling_rights() {
+    assert_eq!(
+        expect_parse_ok("r3k3/8/8/8/8/8/8/R4K1R w KQq - 0 1").validate(),
+        vec![
+            PositionError::CastlingWithoutKing(CastlingRights::WhiteOO),
+            PositionError::CastlingWithoutKing(CastlingRights::WhiteOOO),
+        ]
+    );
+    assert_eq!(
+        expect_parse_ok("4k3/8/8/8/8/8/8/4K3 w k - 0 1").validate(),
+        vec![PositionError::CastlingWithoutRook(CastlingRights::BlackOO)]
+    );
+}
+
+#[test]
+fn test_validate_en_passant() {
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - e6 0 1",
+        "4k3/4p3/8/4p3/8/8/8/4K3 w - e6 0 1",
+        "4k3/8/8/3p4/8/8/8/4K3 b - e3 0 1",
+    ] {
+        assert!(matches!(
+            expect_parse_ok(fen).validate()[..],
+            [PositionError::InvalidEnPassantSquare(_)]
+        ));
+    }
+}
+
+#[test]
+fn test_validate_opponent_in_check() {
+    let fen = "4k3/8/8/8/8/8/8/4K2R b - - 0 1";
+    assert_eq!(expect_parse_ok(fen).validate(), vec![]);
+
+    let fen = "4k3/8/8/8/8/8/8/4K2R w - - 0 1";
+    assert_eq!(expect_parse_ok(fen).validate(), vec![]);
+
+    let fen = "4k2R/8/8/8/8/8/8/4K3 w - - 0 1";
+    assert_eq!(
+        expect_parse_ok(fen).validate(),
+        vec![PositionError::OpponentInCheck]
+    );
+    assert!(matches!(
+        Board::from_fen_strict(fen),
+        Err(StrictFenError::Illegal(_))
+    ));
+    assert!(matches!(
+        Board::from_fen_strict("4k2R/8/8/8/8/8/8/4K3 x - - 0 1"),
+        Err(StrictFenError::Syntax(FenError::InvalidSideToMove { .. }))
+    ));
+}
+
+fn see_value(fen: &str, uci: &str) -> i32 {
+    let board = Board::from_str(fen).unwrap();
+    board.see_value(&board.parse_uci_move(uci).unwrap())
+}
+
+#[test]
+fn test_see_values() {
+    // Free pawn
+    assert_eq!(
+        see_value("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1e5"),
+        100
+    );
+    // A rook for a pawn, unless the second rook behind it takes back
+    assert_eq!(
+        see_value("3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1", "d2d5"),
+        -400
+    );
+    assert_eq!(
+        see_value("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5"),
+        100
+    );
+    // Nothing to take, and the knight is lost
+    assert_eq!(see_value("4k3/8/4p3/8/4N3/8/8/4K3 w - - 0 1", "e4d6"), 0);
+    assert_eq!(see_value("4k3/2p5/8/8/4N3/8/8/4K3 w - - 0 1", "e4d6"), -300);
+    // The king can't take back while the rook behind the queen guards d7
+    assert_eq!(see_value("3k4/3p4/8/8/8/8/3Q4/4K3 w - - 0 1", "d2d7"), -800);
+    assert_eq!(see_value("3k4/3p4/8/8/8/8/3Q4/3RK3 w - - 0 1", "d2d7"), 100);
+    // En passant and a capturing promotion
+    assert_eq!(see_value("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1", "d5e6"), 100);
+    assert_eq!(see_value("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7a8q"), 1300);
+    assert_eq!(
+        see_value("r3k3/1P6/1n6/8/8/8/8/4K3 w - - 0 1", "b7a8q"),
+        400
+    );
+    // Castling never exchanges anything
+    assert_eq!(see_value("4k3/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"), 0);
+}
+
+#[test]
+fn test_see_threshold_matches_value() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+        "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ];
+
+    for fen in fens {
+        let mut board = Board::from_str(fen).unwrap();
+        for m in &board.generate_moves() {
+            board.make_move(m);
+            // Every reply, so both colors and quiet moves get checked too
+            for reply in &board.generate_moves() {
+                let value = board.see_value(reply);
+                for threshold in (-1000..=1000).step_by(50) {
+                    assert_eq!(
+                        board.see(reply, threshold),
+                        value >= threshold,
+                        "{reply} at {} with threshold {threshold}",
+                        board.to_fen()
+                    );
+                }
+            }
+            board.unmake_move();
+        }
+    }
+}
+
+#[test]
+fn test_attackers_to() {
+    use crate::tables::FILE_MASKS;
+
+    let board =
+        Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let all = board.color_bb[0] | board.color_bb[1];
+
+    // With the real occupancy, and with the e-file cleared so the rooks and queen see further
+    for occupied in [
+        all,
+        all & !FILE_MASKS[4] | board.piece_bb[Piece::WKing.to_index()],
+    ] {
+        for to in 0..64 {
+            let to = unsafe { Square::from_int(to) };
+
+            let mut expected = Bitboard(0);
+            let mut pieces = occupied;
+            while !pieces.is_empty() {
+                let from = pieces.pop_lsb();
+                if board.mailbox[from.as_int() as usize]
+                    .attacks(from, occupied)
+                    .contains(to)
+                {
+                    expected |= from.to_bitboard();
+                }
+            }
+
+            assert_eq!(
+                board.attackers_to(to, occupied) & occupied,
+                expected,
+                "{to}"
+            );
+        }
+    }
 }
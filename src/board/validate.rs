@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Board, FenError};
+use crate::types::{CastlingRights, Color, Piece, Rank, Square};
+
+// Things that can make a syntactically valid position impossible to reach
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionError {
+    MissingKing(Color),
+    TooManyKings(Color),
+    TooManyPawns(Color),
+    TooManyPieces(Color),
+    TooManyPromotions(Color),
+    PawnOnBackRank(Square),
+    CastlingWithoutKing(CastlingRights),
+    CastlingWithoutRook(CastlingRights),
+    InvalidEnPassantSquare(Square),
+    OpponentInCheck,
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |c: &Color| if *c == Color::WHITE { "White" } else { "Black" };
+
+        match self {
+            Self::MissingKing(c) => write!(f, "{} has no king", side(c)),
+            Self::TooManyKings(c) => write!(f, "{} has more than one king", side(c)),
+            Self::TooManyPawns(c) => write!(f, "{} has more than eight pawns", side(c)),
+            Self::TooManyPieces(c) => write!(f, "{} has more than sixteen pieces", side(c)),
+            Self::TooManyPromotions(c) => {
+                write!(f, "{} has more promoted pieces than missing pawns", side(c))
+            }
+            Self::PawnOnBackRank(s) => write!(f, "Pawn on the back rank on {s}"),
+            Self::CastlingWithoutKing(r) => write!(f, "{r:?} castling without the king at home"),
+            Self::CastlingWithoutRook(r) => write!(f, "{r:?} castling without the rook at home"),
+            Self::InvalidEnPassantSquare(s) => {
+                write!(f, "No pawn could have just double pushed past {s}")
+            }
+            Self::OpponentInCheck => f.write_str("The side not to move is in check"),
+        }
+    }
+}
+
+impl Error for PositionError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrictFenError {
+    Syntax(FenError),
+    Illegal(Vec<PositionError>),
+}
+
+impl From<FenError> for StrictFenError {
+    fn from(e: FenError) -> Self {
+        Self::Syntax(e)
+    }
+}
+
+impl fmt::Display for StrictFenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(e) => e.fmt(f),
+            Self::Illegal(errors) => {
+                f.write_str("Illegal position: ")?;
+                for (i, e) in errors.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    e.fmt(f)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for StrictFenError {}
+
+impl Board {
+    // Like from_str, but also rejects positions that fail `validate`
+    #[allow(dead_code)]
+    pub fn from_fen_strict(fen: &str) -> Result<Self, StrictFenError> {
+        let board = Self::from_str(fen)?;
+        let errors = board.validate();
+
+        if errors.is_empty() {
+            Ok(board)
+        } else {
+            Err(StrictFenError::Illegal(errors))
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn validate(&self) -> Vec<PositionError> {
+        let mut errors = Vec::new();
+
+        for color in [Color::WHITE, Color::BLACK] {
+            match self.piece_count[Piece::WKing.with_color(color).to_index()] {
+                0 => errors.push(PositionError::MissingKing(color)),
+                1 => {}
+                _ => errors.push(PositionError::TooManyKings(color)),
+            }
+
+            let count =
+                |piece: Piece| usize::from(self.piece_count[piece.with_color(color).to_index()]);
+            let pawns = count(Piece::WPawn);
+            if pawns > 8 {
+                errors.push(PositionError::TooManyPawns(color));
+            }
+
+            let pieces = [
+                Piece::WPawn,
+                Piece::WKnight,
+                Piece::WBishop,
+                Piece::WRook,
+                Piece::WQueen,
+                Piece::WKing,
+            ];
+            if pieces.into_iter().map(count).sum::<usize>() > 16 {
+                errors.push(PositionError::TooManyPieces(color));
+            }
+
+            // Everything past the starting set was a pawn once
+            let promoted = count(Piece::WKnight).saturating_sub(2)
+                + count(Piece::WBishop).saturating_sub(2)
+                + count(Piece::WRook).saturating_sub(2)
+                + count(Piece::WQueen).saturating_sub(1);
+            if promoted > 8_usize.saturating_sub(pawns) {
+                errors.push(PositionError::TooManyPromotions(color));
+            }
+        }
+
+        let mut pawns = (self.piece_bb[Piece::WPawn.to_index()]
+            | self.piece_bb[Piece::BPawn.to_index()])
+            & (Rank::ONE.mask() | Rank::EIGHT.mask());
+        while !pawns.is_empty() {
+            errors.push(PositionError::PawnOnBackRank(pawns.pop_lsb()));
+        }
+
+        self.validate_castling(&mut errors);
+        self.validate_en_passant(&mut errors);
+
+        let them = !self.side_to_move;
+        let king = self.piece_bb[Piece::WKing.with_color(them).to_index()];
+        if !(king & self.state.attacks[self.side_to_move.index()]).is_empty() {
+            errors.push(PositionError::OpponentInCheck);
+        }
+
+        errors
+    }
+
+    fn validate_castling(&self, errors: &mut Vec<PositionError>) {
+        let rights = [
+            (
+                CastlingRights::WhiteOO,
+                Piece::WKing,
+                Square::E1,
+                Square::H1,
+            ),
+            (
+                CastlingRights::WhiteOOO,
+                Piece::WKing,
+                Square::E1,
+                Square::A1,
+            ),
+            (
+                CastlingRights::BlackOO,
+                Piece::BKing,
+                Square::E8,
+                Square::H8,
+            ),
+            (
+                CastlingRights::BlackOOO,
+                Piece::BKing,
+                Square::E8,
+                Square::A8,
+            ),
+        ];
+
+        for (right, king, king_square, rook_square) in rights {
+            if self.state.castling_rights & right.as_int() == 0 {
+                continue;
+            }
+
+            if self.mailbox[king_square as usize] != king {
+                errors.push(PositionError::CastlingWithoutKing(right));
+            }
+
+            let rook = Piece::WRook.with_color(king.color());
+            if self.mailbox[rook_square as usize] != rook {
+                errors.push(PositionError::CastlingWithoutRook(right));
+            }
+        }
+    }
+
+    fn validate_en_passant(&self, errors: &mut Vec<PositionError>) {
+        let ep = self.state.ep_square;
+        if ep == Square::None {
+            return;
+        }
+
+        // `from_str` already made sure it is on the right rank. The pawn went from `origin` past `ep` to `pushed`
+        let (origin, pushed) = if self.side_to_move == Color::WHITE {
+            (ep.as_int() + 8, ep.as_int() - 8)
+        } else {
+            (ep.as_int() - 8, ep.as_int() + 8)
+        };
+
+        if self.mailbox[ep as usize] != Piece::None
+            || self.mailbox[origin as usize] != Piece::None
+            || self.mailbox[pushed as usize] != Piece::WPawn.with_color(!self.side_to_move)
+        {
+            errors.push(PositionError::InvalidEnPassantSquare(ep));
+        }
+    }
+}
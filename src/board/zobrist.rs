@@ -0,0 +1,153 @@
+use super::Board;
+use crate::types::{Color, Piece, Square};
+use crate::utils::splitmix64;
+
+struct Keys {
+    piece_square: [[u64; 64]; Piece::N_PIECES],
+    castling: [u64; 16],
+    ep_file: [u64; 8],
+    side: u64,
+}
+
+static KEYS: Keys = {
+    let mut seed = 0x7469_6e61_7469_6e00;
+
+    let mut piece_square = [[0; 64]; Piece::N_PIECES];
+    let mut i = 0;
+    while i < Piece::N_PIECES * 64 {
+        piece_square[i / 64][i % 64] = splitmix64(&mut seed);
+        i += 1;
+    }
+
+    // One key per CastlingRights bit, every combination is the xor of its bits
+    let bits = [
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+    ];
+    let mut castling = [0; 16];
+    let mut i = 0;
+    while i < castling.len() {
+        let mut bit = 0;
+        while bit < bits.len() {
+            if i & (1 << bit) != 0 {
+                castling[i] ^= bits[bit];
+            }
+            bit += 1;
+        }
+        i += 1;
+    }
+
+    let mut ep_file = [0; 8];
+    let mut i = 0;
+    while i < ep_file.len() {
+        ep_file[i] = splitmix64(&mut seed);
+        i += 1;
+    }
+
+    Keys {
+        piece_square,
+        castling,
+        ep_file,
+        side: splitmix64(&mut seed),
+    }
+};
+
+#[inline]
+pub(super) fn piece_square_key(piece: Piece, square: Square) -> u64 {
+    KEYS.piece_square[piece.to_index()][square.as_int() as usize]
+}
+
+#[inline]
+pub(super) fn castling_key(castling_rights: u8) -> u64 {
+    KEYS.castling[castling_rights as usize & 15]
+}
+
+#[inline]
+pub(super) fn ep_key(ep_square: Square) -> u64 {
+    KEYS.ep_file[ep_square.file() as usize]
+}
+
+#[inline]
+pub(super) fn side_key() -> u64 {
+    KEYS.side
+}
+
+impl Board {
+    // Zobrist key of the position, maintained incrementally by make_move
+    #[inline]
+    #[allow(dead_code)]
+    pub fn hash(&self) -> u64 {
+        self.state.hash
+    }
+
+    // Whether the position already occurred since the last irreversible move
+    pub fn is_repetition(&self) -> bool {
+        let mut plies = 2;
+        let mut state = self
+            .state
+            .previous
+            .as_ref()
+            .and_then(|x| x.previous.as_ref());
+
+        while let Some(s) = state {
+            if plies > u32::from(self.state.rule_50) {
+                break;
+            }
+
+            if s.hash == self.state.hash {
+                return true;
+            }
+
+            plies += 2;
+            state = s.previous.as_ref().and_then(|x| x.previous.as_ref());
+        }
+
+        false
+    }
+
+    // The en passant square only counts if a pawn can actually take on it, so that the same
+    // position reached with and without a pointless double push hashes the same.
+    pub(super) fn ep_hash(&self) -> u64 {
+        let ep = self.state.ep_square;
+        if ep == Square::None {
+            return 0;
+        }
+
+        // Our pawns that can take on ep are the ones an enemy pawn standing there would attack
+        let pawns = self.piece_bb[Piece::WPawn.with_color(self.side_to_move).to_index()];
+        let attackers = Piece::WPawn
+            .with_color(!self.side_to_move)
+            .attacks(ep, pawns)
+            & pawns;
+        if attackers.is_empty() { 0 } else { ep_key(ep) }
+    }
+
+    pub(crate) fn compute_hash(&self) -> u64 {
+        let mut hash = castling_key(self.state.castling_rights) ^ self.ep_hash();
+        if self.side_to_move != Color::WHITE {
+            hash ^= side_key();
+        }
+
+        for (square, &piece) in self.mailbox.iter().enumerate() {
+            if piece != Piece::None {
+                hash ^= piece_square_key(piece, unsafe { Square::from_int(square as u8) });
+            }
+        }
+
+        hash
+    }
+
+    pub(crate) fn compute_pawn_key(&self) -> u64 {
+        let mut key = 0;
+        for pawn in [Piece::WPawn, Piece::BPawn] {
+            let mut pawns = self.piece_bb[pawn.to_index()];
+            while !pawns.is_empty() {
+                key ^= piece_square_key(pawn, pawns.pop_lsb());
+            }
+        }
+
+        key
+    }
+}
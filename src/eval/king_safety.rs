@@ -0,0 +1,128 @@
+use super::S;
+use crate::board::Board;
+use crate::tables::{FILE_MASKS, FORWARD_RANKS_MASKS};
+use crate::types::{Bitboard, Color, Piece, Square};
+
+// Knights, bishops, rooks and queens
+const ATTACKERS: [Piece; 4] = [Piece::WKnight, Piece::WBishop, Piece::WRook, Piece::WQueen];
+// Attack units for each king zone square a piece attacks, and for each safe check it has
+const ZONE_ATTACK_UNITS: [usize; 4] = [2, 2, 3, 5];
+const SAFE_CHECK_UNITS: [usize; 4] = [3, 2, 4, 4];
+
+// A lone attacker is rarely dangerous, so zone attacks only count from this many pieces on
+const MIN_ATTACKERS: usize = 2;
+
+// Attack units to a penalty. Grows slowly for the odd attack and fast once several pieces
+// join in, up to a cap.
+pub(super) const SAFETY_TABLE: [i32; 64] = {
+    let mut array = [0; 64];
+    let mut i = 0;
+    while i < array.len() {
+        let v = (i * i / 4) as i32;
+        array[i] = if v < 500 { v } else { 500 };
+        i += 1;
+    }
+
+    array
+};
+
+// Indexed by how many ranks in front of the king our closest pawn on a file is, 7 if there is
+// none. The files are the king's and its neighbours.
+const SHIELD: [i32; 8] = [0, 0, -10, -20, -25, -30, -30, -35];
+// Indexed by how many ranks in front of the king their closest pawn on a file is
+const STORM: [i32; 8] = [0, -5, -30, -15, -5, 0, 0, 0];
+const SEMI_OPEN_FILE: i32 = -15;
+const OPEN_FILE: i32 = -25;
+
+// Danger to `us`'s king. Everything here is a middlegame term, with only a little of the
+// attack penalty left in the endgame.
+pub(super) fn king_safety(board: &Board, us: Color) -> S {
+    let king = board.piece_bb[Piece::WKing.with_color(us).to_index()];
+    if king.is_empty() {
+        return S::default();
+    }
+
+    let king = king.lsb();
+    let danger = SAFETY_TABLE[attack_units(board, us, king).min(SAFETY_TABLE.len() - 1)];
+
+    S(shelter(board, us, king) - danger, -danger / 8)
+}
+
+fn attack_units(board: &Board, us: Color, king: Square) -> usize {
+    let them = !us;
+    let zone = Piece::WKing.attacks(king, Bitboard(0)) | king.to_bitboard();
+    let occupied = board.color_bb[0] | board.color_bb[1];
+
+    // Checks from squares we don't defend and they don't stand on
+    let safe = !(board.state.attacks[us.index()] | board.color_bb[them.index()]);
+    // Nothing of theirs reaches the zone or a safe square, so there is nothing to count
+    if (board.state.attacks[them.index()] & (zone | safe)).is_empty() {
+        return 0;
+    }
+
+    let mut attackers = 0;
+    let mut zone_units = 0;
+    let mut check_units = 0;
+
+    for (i, piece) in ATTACKERS.into_iter().enumerate() {
+        let checks = piece.attacks(king, occupied) & safe;
+
+        let mut pieces = board.piece_bb[piece.with_color(them).to_index()];
+        while !pieces.is_empty() {
+            let attacks = piece.attacks(pieces.pop_lsb(), occupied);
+
+            let zone_attacks = (attacks & zone).count() as usize;
+            if zone_attacks > 0 {
+                attackers += 1;
+                zone_units += ZONE_ATTACK_UNITS[i] * zone_attacks;
+            }
+
+            check_units += SAFE_CHECK_UNITS[i] * (attacks & checks).count() as usize;
+        }
+    }
+
+    if attackers < MIN_ATTACKERS {
+        zone_units = 0;
+    }
+
+    zone_units + check_units
+}
+
+// Pawn shield, pawn storm and open files on the king's file and the ones next to it
+fn shelter(board: &Board, us: Color, king: Square) -> i32 {
+    let ours = board.piece_bb[Piece::WPawn.with_color(us).to_index()];
+    let theirs = board.piece_bb[Piece::WPawn.with_color(!us).to_index()];
+    let forward = FORWARD_RANKS_MASKS[us.index()][king.rank() as usize];
+
+    // Middle of the three files, kept off the edge so there are always three
+    let center = king.file().clamp(1, 6) as usize;
+    let mut score = 0;
+
+    for &mask in &FILE_MASKS[center - 1..=center + 1] {
+        let closest = |pawns: Bitboard| {
+            let pawns = pawns & mask & forward;
+            if pawns.is_empty() {
+                7
+            } else {
+                let square = if us == Color::WHITE {
+                    pawns.lsb()
+                } else {
+                    pawns.msb()
+                };
+                square.rank().abs_diff(king.rank()) as usize
+            }
+        };
+
+        score += SHIELD[closest(ours)] + STORM[closest(theirs)];
+
+        if (ours & mask).is_empty() {
+            score += if (theirs & mask).is_empty() {
+                OPEN_FILE
+            } else {
+                SEMI_OPEN_FILE
+            };
+        }
+    }
+
+    score
+}
@@ -0,0 +1,183 @@
+mod king_safety;
+mod pawns;
+mod psqt;
+#[cfg(test)]
+mod test;
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+pub use self::pawns::PawnTable;
+
+use self::king_safety::king_safety;
+use self::pawns::{PawnEntry, passed_king_proximity};
+use self::psqt::{FLIP_RANKS, PSQT};
+use crate::board::Board;
+use crate::search::Score;
+use crate::tables::FILE_MASKS;
+use crate::types::{Bitboard, Color, Direction, Piece};
+
+// A middlegame and an endgame score, blended by the game phase at the end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct S(pub i32, pub i32);
+
+impl Add for S {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for S {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl Mul<i32> for S {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl Neg for S {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0, -self.1)
+    }
+}
+
+impl AddAssign for S {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for S {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+// Knights and bishops count 1, rooks 2 and queens 4, so the starting position is MAX_PHASE
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+const MAX_PHASE: i32 = 24;
+
+const BISHOP_PAIR: S = S(30, 50);
+const ROOK_OPEN_FILE: S = S(25, 10);
+const ROOK_SEMI_OPEN_FILE: S = S(12, 8);
+
+// Per square a piece can go to, and the square count that is neither good nor bad, for
+// knights, bishops, rooks and queens
+const MOBILITY: [(S, i32); 4] = [(S(4, 4), 4), (S(5, 5), 6), (S(2, 4), 6), (S(1, 2), 12)];
+
+const PIECES: [Piece; 6] = [
+    Piece::WPawn,
+    Piece::WKnight,
+    Piece::WBishop,
+    Piece::WRook,
+    Piece::WQueen,
+    Piece::WKing,
+];
+
+// 24 in the opening down to 0 with only kings and pawns left. Promotions can push the count
+// past the start, so it is capped.
+pub fn phase(board: &Board) -> i32 {
+    let mut phase = 0;
+    for (i, weight) in PHASE_WEIGHTS.iter().enumerate() {
+        phase += weight * i32::from(board.piece_count[i] + board.piece_count[i + 6]);
+    }
+
+    phase.min(MAX_PHASE)
+}
+
+// Centipawns from the side to move's point of view
+#[allow(dead_code)]
+pub fn evaluate(board: &Board) -> Score {
+    evaluate_with_pawns(board, &PawnEntry::new(board))
+}
+
+// Same as `evaluate`, with the pawn structure looked up in `pawn_table`
+pub fn evaluate_cached(board: &Board, pawn_table: &mut PawnTable) -> Score {
+    evaluate_with_pawns(board, &pawn_table.probe(board))
+}
+
+fn evaluate_with_pawns(board: &Board, pawns: &PawnEntry) -> Score {
+    let score = evaluate_side(board, Color::WHITE) - evaluate_side(board, Color::BLACK)
+        + pawns.score
+        + passed_king_proximity(board, pawns, Color::WHITE)
+        - passed_king_proximity(board, pawns, Color::BLACK)
+        + king_safety(board, Color::WHITE)
+        - king_safety(board, Color::BLACK);
+
+    let phase = phase(board);
+    let score = (score.0 * phase + score.1 * (MAX_PHASE - phase)) / MAX_PHASE;
+
+    if board.side_to_move == Color::WHITE {
+        score
+    } else {
+        -score
+    }
+}
+
+fn pawn_attacks(pawns: Bitboard, color: Color) -> Bitboard {
+    let (left, right) = if color == Color::WHITE {
+        (Direction::NorthWest, Direction::NorthEast)
+    } else {
+        (Direction::SouthWest, Direction::SouthEast)
+    };
+
+    pawns.shift_by_direction(left) | pawns.shift_by_direction(right)
+}
+
+fn rook_file(file: Bitboard, our_pawns: Bitboard, their_pawns: Bitboard) -> S {
+    if !(file & our_pawns).is_empty() {
+        S::default()
+    } else if (file & their_pawns).is_empty() {
+        ROOK_OPEN_FILE
+    } else {
+        ROOK_SEMI_OPEN_FILE
+    }
+}
+
+fn evaluate_side(board: &Board, us: Color) -> S {
+    let them = !us;
+    let flip = if us == Color::WHITE { 0 } else { FLIP_RANKS };
+    let bb = |piece: Piece, color: Color| board.piece_bb[piece.with_color(color).to_index()];
+
+    let occupied = board.color_bb[0] | board.color_bb[1];
+    let our_pawns = bb(Piece::WPawn, us);
+    let their_pawns = bb(Piece::WPawn, them);
+    // Squares attacked by enemy pawns or taken by our own pieces are not worth much to move to
+    let mobility_area = !(board.color_bb[us.index()] | pawn_attacks(their_pawns, them));
+
+    let mut score = S::default();
+    for (i, piece) in PIECES.into_iter().enumerate() {
+        let mut pieces = bb(piece, us);
+        while !pieces.is_empty() {
+            let square = pieces.pop_lsb();
+            score += PSQT[i][square.as_int() as usize ^ flip];
+
+            if (1..=4).contains(&i) {
+                let (weight, neutral) = MOBILITY[i - 1];
+                let moves = piece.attacks(square, occupied) & mobility_area;
+                score += weight * (moves.count() as i32 - neutral);
+            }
+
+            if piece == Piece::WRook {
+                score += rook_file(FILE_MASKS[square.file() as usize], our_pawns, their_pawns);
+            }
+        }
+    }
+
+    if board.piece_count[Piece::WBishop.with_color(us).to_index()] >= 2 {
+        score += BISHOP_PAIR;
+    }
+
+    score
+}
@@ -0,0 +1,184 @@
+use super::{S, pawn_attacks};
+use crate::board::Board;
+use crate::tables::{ADJACENT_FILE_MASKS, FILE_MASKS, FORWARD_RANKS_MASKS, RANK_MASKS};
+use crate::types::{Bitboard, Color, Piece, Square};
+
+// Indexed by rank from the pawn's own side, rank 2 is 1
+const PASSED: [S; 8] = [
+    S(0, 0),
+    S(5, 10),
+    S(5, 15),
+    S(10, 25),
+    S(25, 45),
+    S(45, 85),
+    S(70, 140),
+    S(0, 0),
+];
+const CONNECTED: [S; 8] = [
+    S(0, 0),
+    S(3, 2),
+    S(5, 4),
+    S(8, 6),
+    S(15, 12),
+    S(25, 25),
+    S(45, 45),
+    S(0, 0),
+];
+const DOUBLED: S = S(-10, -25);
+const ISOLATED: S = S(-6, -12);
+pub(super) const BACKWARD: S = S(-8, -10);
+
+// Per rank past the third, the endgame bonus for each square the enemy king is away from the
+// passer's stop square, and the penalty for each square our own king is away
+const PASSED_THEIR_KING: i32 = 5;
+const PASSED_OUR_KING: i32 = 2;
+
+const TABLE_SIZE: usize = 1 << 14;
+
+// Everything about the pawns that only depends on where the pawns are
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PawnEntry {
+    key: u64,
+    // White's minus black's
+    pub(super) score: S,
+    pub(super) passed: [Bitboard; 2],
+}
+
+impl PawnEntry {
+    pub fn new(board: &Board) -> Self {
+        let (white, white_passed) = evaluate_side(board, Color::WHITE);
+        let (black, black_passed) = evaluate_side(board, Color::BLACK);
+
+        Self {
+            key: board.state.pawn_key,
+            score: white - black,
+            passed: [white_passed, black_passed],
+        }
+    }
+}
+
+pub struct PawnTable {
+    entries: Box<[PawnEntry]>,
+}
+
+impl PawnTable {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![PawnEntry::default(); TABLE_SIZE].into_boxed_slice(),
+        }
+    }
+
+    // Positions without pawns have a key of zero, which empty entries already answer correctly
+    pub fn probe(&mut self, board: &Board) -> PawnEntry {
+        let key = board.state.pawn_key;
+        let entry = &mut self.entries[key as usize & (TABLE_SIZE - 1)];
+        if entry.key != key {
+            *entry = PawnEntry::new(board);
+        }
+
+        *entry
+    }
+}
+
+impl Default for PawnTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const fn relative_rank(square: Square, color: Color) -> usize {
+    if color.0 {
+        7 - square.rank() as usize
+    } else {
+        square.rank() as usize
+    }
+}
+
+fn evaluate_side(board: &Board, us: Color) -> (S, Bitboard) {
+    let them = !us;
+    let ours = board.piece_bb[Piece::WPawn.with_color(us).to_index()];
+    let theirs = board.piece_bb[Piece::WPawn.with_color(them).to_index()];
+    let their_attacks = pawn_attacks(theirs, them);
+
+    let mut score = S::default();
+    let mut passed = Bitboard(0);
+
+    let mut pawns = ours;
+    while !pawns.is_empty() {
+        let square = pawns.pop_lsb();
+        let bb = square.to_bitboard();
+        let rank = relative_rank(square, us);
+
+        let file = FILE_MASKS[square.file() as usize];
+        let adjacent = ADJACENT_FILE_MASKS[square.file() as usize];
+        let forward = FORWARD_RANKS_MASKS[us.index()][square.rank() as usize];
+
+        let doubled = !(ours & file & forward).is_empty();
+        let isolated = (ours & adjacent).is_empty();
+        // Defended by a pawn, or standing next to one
+        let connected = !(ours & pawn_attacks(bb, them)).is_empty()
+            || !(ours & adjacent & RANK_MASKS[square.rank() as usize]).is_empty();
+        // Every neighbour has gone past it, and it can't safely step up to them
+        let backward = !isolated
+            && (ours & adjacent & !forward).is_empty()
+            && !(stop_square(bb, us) & their_attacks).is_empty();
+
+        if doubled {
+            score += DOUBLED;
+        }
+        if isolated {
+            score += ISOLATED;
+        } else if backward {
+            score += BACKWARD;
+        }
+        if connected {
+            score += CONNECTED[rank];
+        }
+
+        // Only the front pawn of a doubled pair can be passed
+        if !doubled && (theirs & (file | adjacent) & forward).is_empty() {
+            passed |= bb;
+            score += PASSED[rank];
+        }
+    }
+
+    (score, passed)
+}
+
+fn stop_square(bb: Bitboard, color: Color) -> Bitboard {
+    if color == Color::WHITE {
+        Bitboard(bb.0 << 8)
+    } else {
+        Bitboard(bb.0 >> 8)
+    }
+}
+
+// Kings matter a lot to passed pawns once the pieces are gone, so this part only counts in the
+// endgame. It depends on the kings and can't be cached with the rest.
+pub(super) fn passed_king_proximity(board: &Board, entry: &PawnEntry, us: Color) -> S {
+    let our_king = board.piece_bb[Piece::WKing.with_color(us).to_index()];
+    let their_king = board.piece_bb[Piece::WKing.with_color(!us).to_index()];
+    if our_king.is_empty() || their_king.is_empty() {
+        return S::default();
+    }
+
+    let (our_king, their_king) = (our_king.lsb(), their_king.lsb());
+    let mut bonus = 0;
+
+    let mut passed = entry.passed[us.index()];
+    while !passed.is_empty() {
+        let square = passed.pop_lsb();
+        let rank = relative_rank(square, us) as i32;
+        // A pawn on the last rank, only there in a broken position, has no stop square
+        if !(3..7).contains(&rank) {
+            continue;
+        }
+
+        let stop = stop_square(square.to_bitboard(), us).lsb();
+        bonus += (rank - 2)
+            * (PASSED_THEIR_KING * i32::from(their_king.distance(stop))
+                - PASSED_OUR_KING * i32::from(our_king.distance(stop)));
+    }
+
+    S(0, bonus)
+}
@@ -0,0 +1,242 @@
+#[cfg(test)]
+use super::*;
+use std::str::FromStr;
+
+fn eval(fen: &str) -> Score {
+    evaluate(&Board::from_str(fen).unwrap())
+}
+
+// The same position with the colors swapped and the board flipped top to bottom
+fn mirror(fen: &str) -> String {
+    let fields = fen.split(' ').collect::<Vec<_>>();
+    let swap_case = |s: &str| {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_uppercase() {
+                    c.to_ascii_lowercase()
+                } else {
+                    c.to_ascii_uppercase()
+                }
+            })
+            .collect::<String>()
+    };
+
+    let placement = fields[0].split('/').rev().collect::<Vec<_>>().join("/");
+    let side = if fields[1] == "w" { "b" } else { "w" };
+    let ep = fields[3]
+        .replace('3', "x")
+        .replace('6', "3")
+        .replace('x', "6");
+
+    format!(
+        "{} {side} {} {ep} {}",
+        swap_case(&placement),
+        swap_case(fields[2]),
+        fields[4..].join(" ")
+    )
+}
+
+const POSITIONS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 b - - 0 10",
+];
+
+#[test]
+fn test_symmetry() {
+    for fen in POSITIONS {
+        assert_eq!(eval(fen), eval(&mirror(fen)), "Asymmetric eval for {fen}");
+    }
+
+    assert_eq!(eval(POSITIONS[0]), 0);
+}
+
+#[test]
+fn test_side_to_move_view() {
+    let white = eval("4k3/8/8/8/8/8/8/3QK3 w - - 0 1");
+    let black = eval("4k3/8/8/8/8/8/8/3QK3 b - - 0 1");
+
+    assert!(white > 800);
+    assert_eq!(white, -black);
+}
+
+#[test]
+fn test_phase() {
+    assert_eq!(phase(&Board::new_starting()), MAX_PHASE);
+    assert_eq!(phase(&Board::from_str(POSITIONS[3]).unwrap()), 4);
+    assert_eq!(
+        phase(&Board::from_str("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap()),
+        0
+    );
+    // Three queens are still no more than the opening
+    assert_eq!(
+        phase(&Board::from_str("qqq1k3/8/8/8/8/8/8/QQQ1K3 w - - 0 1").unwrap()),
+        MAX_PHASE
+    );
+}
+
+#[test]
+fn test_bishop_pair() {
+    let pair = eval("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1");
+    let knight_and_bishop = eval("4k3/8/8/8/8/8/8/2B1KN2 w - - 0 1");
+
+    assert!(pair - knight_and_bishop >= BISHOP_PAIR.1);
+}
+
+#[test]
+fn test_rook_files() {
+    let board = Board::from_str("4k3/pp1p1ppp/8/8/8/8/PP2P1PP/4K3 w - - 0 1").unwrap();
+    let white = board.piece_bb[Piece::WPawn.to_index()];
+    let black = board.piece_bb[Piece::BPawn.to_index()];
+
+    assert_eq!(rook_file(FILE_MASKS[2], white, black), ROOK_OPEN_FILE);
+    assert_eq!(rook_file(FILE_MASKS[3], white, black), ROOK_SEMI_OPEN_FILE);
+    assert_eq!(rook_file(FILE_MASKS[4], white, black), S::default());
+    assert_eq!(rook_file(FILE_MASKS[0], white, black), S::default());
+}
+
+#[test]
+fn test_mobility() {
+    // The same knight boxed in by its own pawns, or free in the center
+    let trapped = eval("4k3/8/8/8/8/P1P5/1P6/1N2K3 w - - 0 1");
+    let free = eval("4k3/8/8/8/3N4/P1P5/1P6/4K3 w - - 0 1");
+
+    assert!(free > trapped);
+}
+
+fn pawns(fen: &str) -> PawnEntry {
+    PawnEntry::new(&Board::from_str(fen).unwrap())
+}
+
+#[test]
+fn test_passed_pawns() {
+    use crate::types::Square;
+
+    let entry = pawns("4k3/8/4p3/3P4/8/1P6/1P5p/4K3 w - - 0 1");
+
+    // e6 guards d5, and only the front one of the b pawns counts
+    assert_eq!(entry.passed[0], Square::B3.to_bitboard());
+    assert_eq!(entry.passed[1], Square::H2.to_bitboard());
+}
+
+#[test]
+fn test_pawn_structure_symmetry() {
+    for fen in POSITIONS {
+        let board = Board::from_str(fen).unwrap();
+        let mirrored = Board::from_str(&mirror(fen)).unwrap();
+
+        assert_eq!(
+            PawnEntry::new(&board).score,
+            -PawnEntry::new(&mirrored).score
+        );
+    }
+}
+
+#[test]
+fn test_pawn_structure_penalties() {
+    // Three connected pawns, against the same three split up
+    let healthy = pawns("4k3/8/8/8/8/8/5PPP/4K3 w - - 0 1").score;
+    let doubled = pawns("4k3/8/8/8/8/6P1/6PP/4K3 w - - 0 1").score;
+    let isolated = pawns("4k3/8/8/8/8/8/P3P2P/4K3 w - - 0 1").score;
+
+    assert!(healthy.0 > doubled.0 && healthy.1 > doubled.1);
+    assert!(healthy.0 > isolated.0 && healthy.1 > isolated.1);
+}
+
+#[test]
+fn test_backward_pawn() {
+    // d3 can't catch up with c4 and e4 without being taken by c5 or e5
+    let backward = pawns("4k3/8/8/2p1p3/2P1P3/3P4/8/4K3 w - - 0 1").score;
+    let level = pawns("4k3/8/8/2p1p3/2PPP3/8/8/4K3 w - - 0 1").score;
+
+    assert!(level.0 - backward.0 >= -pawns::BACKWARD.0);
+}
+
+#[test]
+fn test_pawn_table() {
+    let mut table = PawnTable::new();
+
+    for fen in POSITIONS {
+        let board = Board::from_str(fen).unwrap();
+        let fresh = PawnEntry::new(&board);
+
+        // Missing, then cached
+        for _ in 0..2 {
+            let cached = table.probe(&board);
+            assert_eq!((cached.score, cached.passed), (fresh.score, fresh.passed));
+        }
+
+        let board = Board::from_str(fen).unwrap();
+        assert_eq!(evaluate_cached(&board, &mut table), evaluate(&board));
+    }
+}
+
+#[test]
+fn test_king_escorts_passed_pawn() {
+    let escorted = eval("8/8/3KP3/8/8/8/8/k7 w - - 0 1");
+    let alone = eval("8/8/4P3/8/8/8/8/k2K4 w - - 0 1");
+    let caught = eval("4k3/8/3KP3/8/8/8/8/8 w - - 0 1");
+
+    assert!(escorted > alone);
+    assert!(escorted > caught);
+}
+
+#[test]
+fn test_pawn_on_last_rank() {
+    // Counts as passed, but has nowhere left to go for the kings to escort it to
+    let fen = "P3k3/8/8/8/8/8/8/4K3 w - - 0 1";
+    assert_eq!(eval(fen), eval(&mirror(fen)));
+}
+
+fn safety(fen: &str, color: Color) -> S {
+    king_safety::king_safety(&Board::from_str(fen).unwrap(), color)
+}
+
+#[test]
+fn test_pawn_shield() {
+    let sheltered = safety("4k3/8/8/8/8/8/5PPP/6K1 w - - 0 1", Color::WHITE);
+    let advanced = safety("4k3/8/8/8/8/5PPP/8/6K1 w - - 0 1", Color::WHITE);
+    let bare = safety("4k3/8/8/8/8/8/8/6K1 w - - 0 1", Color::WHITE);
+
+    assert!(sheltered.0 > advanced.0);
+    assert!(advanced.0 > bare.0);
+    // All of it fades out in the endgame
+    assert_eq!((sheltered.1, bare.1), (0, 0));
+}
+
+#[test]
+fn test_pawn_storm() {
+    let calm = safety("4k3/8/5ppp/8/8/8/5PPP/6K1 w - - 0 1", Color::WHITE);
+    let coming = safety("4k3/8/8/8/5ppp/8/5PPP/6K1 w - - 0 1", Color::WHITE);
+    let close = safety("4k3/8/8/8/8/5ppp/5PPP/6K1 w - - 0 1", Color::WHITE);
+
+    assert!(calm.0 > coming.0);
+    assert!(coming.0 > close.0);
+}
+
+#[test]
+fn test_king_attack() {
+    let quiet = safety("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1", Color::WHITE);
+    // A lone queen next to the king is not an attack yet, a rook joining in is
+    let lone = safety("6k1/8/8/8/8/6q1/5PPP/6K1 w - - 0 1", Color::WHITE);
+    let attacked = safety("6k1/8/8/8/8/6q1/5PPP/5r1K w - - 0 1", Color::WHITE);
+    // Safe checks count even from afar, a1 is not defended
+    let checks = safety("r5k1/8/8/8/8/8/5PPP/6K1 w - - 0 1", Color::WHITE);
+
+    assert_eq!(lone, quiet);
+    assert!(attacked.0 < lone.0 && attacked.1 < 0);
+    assert!(checks.0 < quiet.0);
+}
+
+#[test]
+fn test_safety_table() {
+    let table = king_safety::SAFETY_TABLE;
+
+    assert_eq!(table[0], 0);
+    assert!(table.windows(2).all(|w| w[0] <= w[1]));
+    // Slow at first, then steeper, then flat
+    assert!(table[4] - table[2] < table[20] - table[18]);
+    assert_eq!(table[table.len() - 1], 500);
+}
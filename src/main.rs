@@ -7,24 +7,133 @@
     incomplete_features,
     long_running_const_eval
 )]
-#![feature(generic_const_exprs, generic_const_items, test)]
+// Only the compile-time attack table needs nightly
+#![cfg_attr(feature = "const_sliding_attacks", feature(generic_const_exprs))]
 mod attacks;
 mod board;
+mod eval;
 mod movegen;
+mod nnue;
+mod perft;
+mod search;
 mod tables;
 mod types;
+mod uci;
 mod utils;
 
+use std::process::ExitCode;
 use std::str::FromStr;
+use std::time::Instant;
 
-use crate::types::Color;
+use crate::attacks::finder::{DEFAULT_SEED, precomputed_rs};
+use crate::board::Board;
+use crate::nnue::Network;
+use crate::uci::Uci;
 
-fn main() {
-    let mut board =
-        board::Board::from_str("rnbqkbnr/p1pp1Qp1/1p5p/1B2p3/4P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 4")
-            .unwrap();
-    board.populate_state();
+const USAGE: &str =
+    "usage: tinatin [perft|divide <depth> [fen] | eval <network> [fen] | magic [seed] [--shrink]]";
 
-    let attacks = board.get_attacks::<{ Color::WHITE.0 }>();
-    println!("{board}\n\n{attacks}");
+fn parse_perft_args(args: &[String]) -> Result<(u32, Board), String> {
+    let depth = args
+        .first()
+        .ok_or("missing depth")?
+        .parse::<u32>()
+        .map_err(|e| format!("invalid depth: {e}"))?;
+
+    let board = if args.len() > 1 {
+        Board::from_str(&args[1..].join(" ")).map_err(|e| e.to_string())?
+    } else {
+        Board::new_starting()
+    };
+
+    Ok((depth, board))
+}
+
+fn perft(args: &[String], divide: bool) -> Result<(), String> {
+    let (depth, mut board) = parse_perft_args(args)?;
+    let start = Instant::now();
+
+    let nodes = if divide {
+        let mut nodes = 0;
+        for (m, n) in board.divide(depth) {
+            println!("{m}: {n}");
+            nodes += n;
+        }
+
+        println!();
+        nodes
+    } else {
+        board.perft(depth)
+    };
+
+    let elapsed = start.elapsed();
+    println!("nodes: {nodes}");
+    println!(
+        "time: {}ms, nps: {}",
+        elapsed.as_millis(),
+        u128::from(nodes) * 1_000_000 / elapsed.as_micros().max(1)
+    );
+
+    Ok(())
+}
+
+// The network's score for a position, from the side to move's point of view
+fn eval(args: &[String]) -> Result<(), String> {
+    let path = args.first().ok_or("missing network file")?;
+    let network = Network::load(path).map_err(|e| format!("{path}: {e}"))?;
+
+    let board = if args.len() > 1 {
+        Board::from_str(&args[1..].join(" ")).map_err(|e| e.to_string())?
+    } else {
+        Board::new_starting()
+    };
+
+    println!("nnue: {}", network.evaluate(&board));
+    Ok(())
+}
+
+// Prints a replacement for attacks/precomputed.rs with freshly found magics
+fn magic(args: &[String]) -> Result<(), String> {
+    let mut seed = DEFAULT_SEED;
+    let mut shrink = false;
+    for arg in args {
+        if arg == "--shrink" {
+            shrink = true;
+        } else {
+            seed = arg.parse().map_err(|e| format!("invalid seed: {e}"))?;
+        }
+    }
+
+    print!("{}", precomputed_rs(seed, shrink));
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    // Built for BMI2 (e.g. with -Ctarget-cpu=native), which a different CPU may not have
+    #[cfg(target_feature = "bmi2")]
+    if !std::arch::is_x86_feature_detected!("bmi2") {
+        eprintln!("this build of tinatin needs a CPU with BMI2");
+        return ExitCode::FAILURE;
+    }
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(String::as_str) {
+        Some("perft") => perft(&args[1..], false),
+        Some("divide") => perft(&args[1..], true),
+        Some("eval") => eval(&args[1..]),
+        Some("magic") => magic(&args[1..]),
+        Some(_) => Err(USAGE.to_owned()),
+
+        None => Uci::new(std::io::stdout())
+            .run(std::io::stdin().lock())
+            .map_err(|e| e.to_string()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
 }
@@ -1,12 +1,21 @@
 #![allow(dead_code)]
 
+mod move_list;
+mod packed_move;
+#[cfg(test)]
+mod test;
+
 use std::fmt::Display;
 
+pub use move_list::MoveList;
+pub use packed_move::PackedMove;
+
 use crate::board::Board;
-use crate::types::{Color, Piece, Square};
+use crate::tables::{BETWEEN_MASKS, LINE_MASKS};
+use crate::types::{BB, Bitboard, CastlingRights, Color, Direction, Piece, Rank, Square};
 
-// Should I reduce the size of this array? yes. Will I? no.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+// Everything about a move at hand, see `PackedMove` for the compact form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct Move {
     pub from: Square,
     pub to: Square,
@@ -16,15 +25,16 @@ pub struct Move {
     pub is_castling: bool,
 }
 
-const MAX_MOVES: usize = 256;
-pub type MoveList = smallvec::SmallVec<[Move; 32]>;
+pub const MAX_MOVES: usize = 256;
+
+const PROMOTIONS: [Piece; 4] = [Piece::WQueen, Piece::WKnight, Piece::WRook, Piece::WBishop];
 
 impl Display for Move {
     // UCI
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}{}", self.from, self.to)?;
         if let Some(promotion) = self.promotion {
-            write!(f, "={}", promotion.type_of_to_piece().to_char())?;
+            write!(f, "{}", promotion.with_color(Color::BLACK).to_char())?;
         }
 
         Ok(())
@@ -32,16 +42,284 @@ impl Display for Move {
 }
 
 impl Board {
+    #[inline]
+    const fn push_moves(&self, movelist: &mut MoveList, from: Square, mut targets: Bitboard) {
+        let moved = self.mailbox[from.as_int() as usize];
+        while !targets.is_empty() {
+            let to = targets.pop_lsb();
+            movelist.push(Move {
+                from,
+                to,
+                moved,
+                captured: self.mailbox[to.as_int() as usize],
+                promotion: None,
+                is_castling: false,
+            });
+        }
+    }
+
+    #[inline]
+    fn push_pawn_moves(&self, movelist: &mut MoveList, from: Square, mut targets: Bitboard) {
+        let moved = self.mailbox[from.as_int() as usize];
+        let last_rank = if moved.color() == Color::WHITE {
+            Rank::EIGHT
+        } else {
+            Rank::ONE
+        };
+
+        while !targets.is_empty() {
+            let to = targets.pop_lsb();
+            let captured = self.mailbox[to.as_int() as usize];
+
+            if to.rank() as usize == last_rank.0 {
+                for promotion in PROMOTIONS {
+                    movelist.push(Move {
+                        from,
+                        to,
+                        moved,
+                        captured,
+                        promotion: Some(promotion.with_color(moved.color())),
+                        is_castling: false,
+                    });
+                }
+            } else {
+                movelist.push(Move {
+                    from,
+                    to,
+                    moved,
+                    captured,
+                    promotion: None,
+                    is_castling: false,
+                });
+            }
+        }
+    }
+
+    // En passant removes two pieces from the same rank at once, which the pin detection can't see,
+    // so we just look at the king after the capture.
+    fn en_passant_is_legal(
+        &self,
+        from: Square,
+        to: Square,
+        captured: Square,
+        king: Square,
+    ) -> bool {
+        let them = !self.side_to_move;
+        let occupied =
+            (self.color_bb[0] | self.color_bb[1]) ^ from.to_bitboard() ^ captured.to_bitboard()
+                | to.to_bitboard();
+
+        // The captured pawn is still in `piece_bb`, so it is masked out by `occupied`
+        (self.attackers_to(king, occupied) & self.color_bb[them.index()] & occupied).is_empty()
+    }
+
+    // With TACTICAL only captures and promotions are generated
+    fn generate_pawn_moves<const TACTICAL: bool>(
+        &self,
+        movelist: &mut MoveList,
+        target: Bitboard,
+        pinned: Bitboard,
+        king: Square,
+    ) {
+        let us = self.side_to_move;
+        let them = !us;
+        let pawn = Piece::WPawn.with_color(us);
+
+        let (up, start_rank, last_rank) = if us == Color::WHITE {
+            (Direction::North, Rank::TWO, Rank::EIGHT)
+        } else {
+            (Direction::South, Rank::SEVEN, Rank::ONE)
+        };
+
+        let occupied = self.color_bb[0] | self.color_bb[1];
+        let theirs = self.color_bb[them.index()];
+
+        // The pawn that can be taken en passant. A FEN may name any square, there has to be a
+        // pawn of theirs right behind it that could have just skipped over it.
+        let ep = self.state.ep_square;
+        let ep_rank = if us == Color::WHITE {
+            Rank::SIX
+        } else {
+            Rank::THREE
+        };
+        let ep_pawn = (ep != Square::None && ep.rank() as usize == ep_rank.0)
+            .then(|| ep.to_bitboard().shift_by_direction(up.inverse()).lsb())
+            .filter(|&captured| {
+                self.mailbox[captured.as_int() as usize] == Piece::WPawn.with_color(them)
+            });
+
+        let mut pawns = self.piece_bb[pawn.to_index()];
+        while !pawns.is_empty() {
+            let from = pawns.pop_lsb();
+            let allowed = if pinned.contains(from) {
+                LINE_MASKS[king.as_int() as usize][from.as_int() as usize]
+            } else {
+                BB(!0)
+            };
+
+            let single = from.to_bitboard().shift_by_direction(up) & !occupied;
+            let mut targets = single;
+            if TACTICAL {
+                targets &= last_rank.mask();
+            } else if from.rank() as usize == start_rank.0 {
+                targets |= single.shift_by_direction(up) & !occupied;
+            }
+
+            targets |= pawn.attacks(from, occupied) & theirs;
+            self.push_pawn_moves(movelist, from, targets & target & allowed);
+
+            if let Some(captured) = ep_pawn
+                && pawn.attacks(from, occupied).contains(ep)
+                && self.en_passant_is_legal(from, ep, captured, king)
+            {
+                movelist.push(Move {
+                    from,
+                    to: ep,
+                    moved: pawn,
+                    captured: Piece::WPawn.with_color(them),
+                    promotion: None,
+                    is_castling: false,
+                });
+            }
+        }
+    }
+
+    fn generate_castling_moves(&self, movelist: &mut MoveList, king: Square) {
+        let us = self.side_to_move;
+        let danger = self.state.attacks[(!us).index()];
+        let occupied = self.color_bb[0] | self.color_bb[1];
+        let rooks = self.piece_bb[Piece::WRook.with_color(us).to_index()];
+
+        let (oo, ooo, home) = if us == Color::WHITE {
+            (
+                CastlingRights::WhiteOO,
+                CastlingRights::WhiteOOO,
+                Square::E1,
+            )
+        } else {
+            (
+                CastlingRights::BlackOO,
+                CastlingRights::BlackOOO,
+                Square::E8,
+            )
+        };
+
+        if king != home {
+            return;
+        }
+
+        let home = home.as_int();
+        let sides = [
+            // right, rook, king's destination, squares that must be empty, squares that must be safe
+            (
+                oo,
+                home + 3,
+                home + 2,
+                BB(0b11 << (home + 1)),
+                BB(0b11 << (home + 1)),
+            ),
+            (
+                ooo,
+                home - 4,
+                home - 2,
+                BB(0b111 << (home - 3)),
+                BB(0b11 << (home - 2)),
+            ),
+        ];
+
+        for (right, rook, to, empty, safe) in sides {
+            if self.state.castling_rights & right.as_int() == 0
+                || !rooks.contains(unsafe { Square::from_int(rook) })
+                || !(occupied & empty).is_empty()
+                || !(danger & safe).is_empty()
+            {
+                continue;
+            }
+
+            movelist.push(Move {
+                from: king,
+                to: unsafe { Square::from_int(to) },
+                moved: Piece::WKing.with_color(us),
+                captured: Piece::None,
+                promotion: None,
+                is_castling: true,
+            });
+        }
+    }
+
+    // Finds the legal move written in UCI long algebraic notation
+    pub fn parse_uci_move(&self, s: &str) -> Option<Move> {
+        let s = s.to_ascii_lowercase();
+        self.generate_moves()
+            .into_iter()
+            .find(|m| m.to_string() == s)
+    }
+
     pub fn generate_moves(&self) -> MoveList {
+        self.generate::<false>()
+    }
+
+    // Legal captures and promotions, including en passant and underpromotions
+    pub fn generate_captures(&self) -> MoveList {
+        self.generate::<true>()
+    }
+
+    fn generate<const TACTICAL: bool>(&self) -> MoveList {
         let mut movelist = MoveList::default();
 
-        if !self.state.checkers.0.is_power_of_two() {
-            let king = self.piece_bb[(if self.side_to_move == Color::WHITE {
-                Piece::WKing
-            } else {
-                Piece::BKing
-            })
-            .to_index()];
+        let us = self.side_to_move;
+        let them = !us;
+
+        let king = self.piece_bb[Piece::WKing.with_color(us).to_index()];
+        // Without a king there is nothing to be legal against
+        if king.is_empty() {
+            return movelist;
+        }
+
+        let king = king.lsb();
+        let ours = self.color_bb[us.index()];
+        let theirs = self.color_bb[them.index()];
+        let occupied = ours | theirs;
+        let checkers = self.state.checkers & theirs;
+
+        let danger = self.state.attacks[them.index()];
+        let mut targets = Piece::WKing.attacks(king, occupied) & !ours & !danger;
+        if TACTICAL {
+            targets &= theirs;
+        }
+        self.push_moves(&mut movelist, king, targets);
+
+        // Double check, only the king can move
+        if checkers.count() > 1 {
+            return movelist;
+        }
+
+        let target = if checkers.is_empty() {
+            !ours
+        } else {
+            BETWEEN_MASKS[king.as_int() as usize][checkers.lsb().as_int() as usize] | checkers
+        };
+        let pinned = self.state.king_blockers[us.index()] & ours;
+
+        // Pawns need the empty squares for promotions, so they narrow it down themselves
+        self.generate_pawn_moves::<TACTICAL>(&mut movelist, target, pinned, king);
+        let target = if TACTICAL { target & theirs } else { target };
+
+        for piece in [Piece::WKnight, Piece::WBishop, Piece::WRook, Piece::WQueen] {
+            let mut pieces = self.piece_bb[piece.with_color(us).to_index()];
+            while !pieces.is_empty() {
+                let from = pieces.pop_lsb();
+                let mut targets = piece.attacks(from, occupied) & target;
+                if pinned.contains(from) {
+                    targets &= LINE_MASKS[king.as_int() as usize][from.as_int() as usize];
+                }
+
+                self.push_moves(&mut movelist, from, targets);
+            }
+        }
+
+        if !TACTICAL && checkers.is_empty() {
+            self.generate_castling_moves(&mut movelist, king);
         }
 
         movelist
@@ -0,0 +1,163 @@
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+
+use super::{MAX_MOVES, Move};
+
+// A list of at most MAX_MOVES moves that lives entirely on the stack. No reachable position has
+// more legal moves than that, so the generator never needs to allocate.
+pub struct MoveList<M: Copy = Move> {
+    moves: [MaybeUninit<M>; MAX_MOVES],
+    len: usize,
+}
+
+impl<M: Copy> MoveList<M> {
+    pub const fn new() -> Self {
+        Self {
+            moves: [const { MaybeUninit::uninit() }; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    // Does nothing once the list is full. Only a position with more pieces than a game can have
+    // (see `validate`) gets there, and it is better to miss some of its moves than to crash.
+    #[inline]
+    pub const fn push(&mut self, m: M) {
+        if self.len < MAX_MOVES {
+            self.moves[self.len].write(m);
+            self.len += 1;
+        }
+    }
+
+    // Removes the move at `index` and puts the last one in its place
+    pub fn swap_remove(&mut self, index: usize) -> M {
+        let m = self[index];
+        self.len -= 1;
+        if index < self.len {
+            self[index] = unsafe { self.moves[self.len].assume_init() };
+        }
+
+        m
+    }
+
+    pub const fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    // Sorts from the highest score to the lowest, keeping the generation order among equal
+    // scores. `score` is called once per move.
+    pub fn sort_by_score(&mut self, mut score: impl FnMut(M) -> i32) {
+        let mut scores = [0; MAX_MOVES];
+        for (i, &m) in self.iter().enumerate() {
+            scores[i] = score(m);
+        }
+
+        // Insertion sort, move lists are short and often close to sorted already
+        for i in 1..self.len {
+            let (s, m) = (scores[i], self[i]);
+
+            let mut j = i;
+            while j > 0 && scores[j - 1] < s {
+                scores[j] = scores[j - 1];
+                self[j] = self[j - 1];
+                j -= 1;
+            }
+
+            scores[j] = s;
+            self[j] = m;
+        }
+    }
+}
+
+impl<M: Copy> Deref for MoveList<M> {
+    type Target = [M];
+
+    #[inline]
+    fn deref(&self) -> &[M] {
+        // The first `len` moves are always initialized
+        unsafe { std::slice::from_raw_parts(self.moves.as_ptr().cast(), self.len) }
+    }
+}
+
+impl<M: Copy> DerefMut for MoveList<M> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [M] {
+        unsafe { std::slice::from_raw_parts_mut(self.moves.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl<M: Copy> Default for MoveList<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Copy> Clone for MoveList<M> {
+    fn clone(&self) -> Self {
+        Self {
+            moves: self.moves,
+            len: self.len,
+        }
+    }
+}
+
+impl<M: Copy + fmt::Debug> fmt::Debug for MoveList<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<M: Copy> FromIterator<M> for MoveList<M> {
+    fn from_iter<I: IntoIterator<Item = M>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for m in iter {
+            list.push(m);
+        }
+
+        list
+    }
+}
+
+impl<'a, M: Copy> IntoIterator for &'a MoveList<M> {
+    type Item = &'a M;
+    type IntoIter = std::slice::Iter<'a, M>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<M: Copy> IntoIterator for MoveList<M> {
+    type Item = M;
+    type IntoIter = IntoIter<M>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            list: self,
+            index: 0,
+        }
+    }
+}
+
+pub struct IntoIter<M: Copy> {
+    list: MoveList<M>,
+    index: usize,
+}
+
+impl<M: Copy> Iterator for IntoIter<M> {
+    type Item = M;
+
+    fn next(&mut self) -> Option<M> {
+        let m = self.list.get(self.index).copied();
+        self.index += 1;
+
+        m
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.list.len.saturating_sub(self.index);
+        (left, Some(left))
+    }
+}
+
+impl<M: Copy> ExactSizeIterator for IntoIter<M> {}
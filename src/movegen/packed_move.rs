@@ -0,0 +1,133 @@
+use std::fmt::Display;
+
+use super::Move;
+use crate::board::Board;
+use crate::types::{Color, Piece, Square};
+
+// A move in 16 bits: from 0..6, to 6..12 and a flag nibble 12..16. The flags are enough to
+// rebuild the full `Move` given the position it is played in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PackedMove(u16);
+
+impl PackedMove {
+    // a1a1, never a legal move
+    pub const NULL: Self = Self(0);
+
+    const QUIET: u16 = 0;
+    const DOUBLE_PUSH: u16 = 1;
+    const CASTLING: u16 = 2;
+    const CAPTURE: u16 = 4;
+    const EN_PASSANT: u16 = 5;
+    // Set for all promotions, the low two bits are the piece and CAPTURE can be set as well
+    const PROMOTION: u16 = 8;
+
+    const PROMOTIONS: [Piece; 4] = [Piece::WKnight, Piece::WBishop, Piece::WRook, Piece::WQueen];
+
+    // `board` is the position the move is about to be played in
+    pub fn new(m: Move, board: &Board) -> Self {
+        let is_pawn = m.moved.type_of_to_piece() == Piece::WPawn;
+
+        let mut flags = if m.is_castling {
+            Self::CASTLING
+        } else if is_pawn && m.to == board.state.ep_square {
+            Self::EN_PASSANT
+        } else if m.captured != Piece::None {
+            Self::CAPTURE
+        } else if is_pawn && m.from.as_int().abs_diff(m.to.as_int()) == 16 {
+            Self::DOUBLE_PUSH
+        } else {
+            Self::QUIET
+        };
+
+        if let Some(promotion) = m.promotion {
+            let piece = promotion.type_of_to_piece();
+            let index = Self::PROMOTIONS.iter().position(|&p| p == piece).unwrap() as u16;
+            flags |= Self::PROMOTION | index;
+        }
+
+        Self(u16::from(m.from.as_int()) | u16::from(m.to.as_int()) << 6 | flags << 12)
+    }
+
+    pub const fn from(self) -> Square {
+        unsafe { Square::from_int((self.0 & 63) as u8) }
+    }
+
+    pub const fn to(self) -> Square {
+        unsafe { Square::from_int((self.0 >> 6 & 63) as u8) }
+    }
+
+    const fn flags(self) -> u16 {
+        self.0 >> 12
+    }
+
+    pub const fn is_null(self) -> bool {
+        self.0 == Self::NULL.0
+    }
+
+    pub const fn is_castling(self) -> bool {
+        self.flags() == Self::CASTLING
+    }
+
+    pub const fn is_en_passant(self) -> bool {
+        self.flags() == Self::EN_PASSANT
+    }
+
+    pub const fn is_double_push(self) -> bool {
+        self.flags() == Self::DOUBLE_PUSH
+    }
+
+    pub const fn is_capture(self) -> bool {
+        self.flags() & Self::CAPTURE != 0
+    }
+
+    // Colorless, as in `PROMOTIONS`
+    pub const fn promotion(self) -> Option<Piece> {
+        if self.flags() & Self::PROMOTION == 0 {
+            None
+        } else {
+            Some(Self::PROMOTIONS[(self.flags() & 3) as usize])
+        }
+    }
+
+    // `board` is the position the move is about to be played in. The result is only legal if
+    // the packed move was made from a legal move in that same position.
+    pub fn to_move(self, board: &Board) -> Move {
+        let moved = board.mailbox[self.from().as_int() as usize];
+        let color = moved.color();
+
+        let captured = if self.is_en_passant() {
+            Piece::WPawn.with_color(!color)
+        } else {
+            board.mailbox[self.to().as_int() as usize]
+        };
+
+        Move {
+            from: self.from(),
+            to: self.to(),
+            moved,
+            captured,
+            promotion: self.promotion().map(|p| p.with_color(color)),
+            is_castling: self.is_castling(),
+        }
+    }
+
+    pub const fn as_u16(self) -> u16 {
+        self.0
+    }
+
+    pub const fn from_u16(x: u16) -> Self {
+        Self(x)
+    }
+}
+
+impl Display for PackedMove {
+    // UCI, like `Move`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.from(), self.to())?;
+        if let Some(promotion) = self.promotion() {
+            write!(f, "{}", promotion.with_color(Color::BLACK).to_char())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+#[cfg(test)]
+use super::*;
+use std::str::FromStr;
+
+fn moves_of(fen: &str) -> MoveList {
+    Board::from_str(fen)
+        .expect("Test FEN should parse")
+        .generate_moves()
+}
+
+fn assert_move_count(fen: &str, expected: usize) {
+    let moves = moves_of(fen);
+
+    for m in &moves {
+        println!("{m}");
+    }
+    assert_eq!(moves.len(), expected, "Wrong number of moves for {fen}");
+}
+
+fn has_move(moves: &MoveList, from: Square, to: Square) -> bool {
+    moves.iter().any(|m| m.from == from && m.to == to)
+}
+
+#[test]
+fn test_starting_position() {
+    assert_move_count(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        20,
+    );
+}
+
+#[test]
+fn test_kiwipete() {
+    assert_move_count(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        48,
+    );
+}
+
+#[test]
+fn test_rook_endgame() {
+    assert_move_count("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 14);
+}
+
+#[test]
+fn test_promotions_and_pins() {
+    assert_move_count(
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        6,
+    );
+    assert_move_count(
+        "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+        6,
+    );
+}
+
+#[test]
+fn test_middlegame_positions() {
+    assert_move_count(
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        44,
+    );
+    assert_move_count(
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        46,
+    );
+}
+
+#[test]
+fn test_double_check_only_king_moves() {
+    let moves = moves_of("4k3/8/8/8/8/5n2/8/r3K3 w - - 0 1");
+
+    assert!(moves.iter().all(|m| m.moved == Piece::WKing));
+}
+
+#[test]
+fn test_castling_through_check() {
+    let moves = moves_of("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1");
+
+    assert!(!has_move(&moves, Square::E1, Square::G1));
+    assert!(has_move(&moves, Square::E1, Square::C1));
+}
+
+#[test]
+fn test_en_passant() {
+    let moves = moves_of("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1");
+    assert!(has_move(&moves, Square::D5, Square::E6));
+
+    // Capturing would leave the king on a5 open to the rook on h5
+    let moves = moves_of("8/8/8/KPp4r/8/8/8/7k w - c6 0 1");
+    assert!(!has_move(&moves, Square::B5, Square::C6));
+}
+
+#[test]
+fn test_en_passant_needs_a_pawn_to_take() {
+    // Neither has a black pawn behind e3, and e3 isn't on white's en passant rank anyway
+    for fen in [
+        "4k3/8/8/8/8/8/3P4/4K3 w - e3 0 1",
+        "4k3/8/8/8/8/8/3PP3/4K3 w - e3 0 1",
+    ] {
+        assert!(Board::from_str(fen).is_err());
+    }
+
+    // Right rank, but nothing on e5
+    let moves = moves_of("4k3/8/8/3P4/8/8/8/4K3 w - e6 0 1");
+    assert!(!has_move(&moves, Square::D5, Square::E6));
+}
+
+#[test]
+fn test_en_passant_evasion() {
+    // The double pushed pawn gives check, taking it en passant is the only pawn move
+    let moves = moves_of("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1");
+    assert!(has_move(&moves, Square::E4, Square::D3));
+    assert!(!has_move(&moves, Square::E4, Square::E3));
+}
+
+#[test]
+fn test_uci_notation() {
+    let moves = moves_of("4k3/1P6/8/8/8/8/8/4K2R w K - 0 1");
+    let moves = moves.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    for m in ["b7b8q", "b7b8n", "b7b8r", "b7b8b", "e1g1", "h1h8"] {
+        assert!(moves.iter().any(|x| x == m), "Missing {m}");
+    }
+}
+
+#[test]
+fn test_captures_are_the_tactical_legal_moves() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        // Promotions with and without a capture, and a pawn pinned on its promotion square
+        "1n2k3/P1P5/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/4p3/2KR1R2 b - - 0 1",
+        "7K/6P1/8/8/8/8/8/b5k1 w - - 0 1",
+        // En passant, and an evasion that must capture
+        "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1",
+        "4k3/8/8/8/8/5n2/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/5n2/6P1/4K3 w - - 0 1",
+    ];
+
+    for fen in fens {
+        let board = Board::from_str(fen).unwrap();
+
+        let mut expected = board
+            .generate_moves()
+            .into_iter()
+            .filter(|m| m.captured != Piece::None || m.promotion.is_some())
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>();
+        let mut captures = board
+            .generate_captures()
+            .into_iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>();
+
+        expected.sort();
+        captures.sort();
+        assert_eq!(captures, expected, "Wrong captures for {fen}");
+    }
+}
+
+#[test]
+fn test_packed_moves_round_trip() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+        "1n2k3/P1P5/8/8/8/8/8/4K3 w - - 0 1",
+        "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1",
+    ];
+
+    for fen in fens {
+        let board = Board::from_str(fen).unwrap();
+        let moves = board.generate_moves();
+        let packed = moves
+            .iter()
+            .map(|&m| PackedMove::new(m, &board))
+            .collect::<MoveList<PackedMove>>();
+
+        for (&m, p) in moves.iter().zip(&packed) {
+            assert_eq!(p.to_move(&board), m);
+            assert_eq!(p.to_string(), m.to_string());
+            assert!(!p.is_null());
+        }
+    }
+}
+
+#[test]
+fn test_packed_move_flags() {
+    let board = Board::from_str("r3k3/1P6/8/3pP3/8/8/3P4/R3K2R w KQq d6 0 1").unwrap();
+    let pack = |uci| PackedMove::new(board.parse_uci_move(uci).unwrap(), &board);
+
+    assert!(pack("e1g1").is_castling());
+    assert!(pack("e1c1").is_castling());
+    assert!(pack("d2d4").is_double_push());
+    assert!(!pack("d2d3").is_double_push());
+
+    let ep = pack("e5d6");
+    assert!(ep.is_en_passant() && ep.is_capture());
+
+    let promotion = pack("b7b8n");
+    assert_eq!(promotion.promotion(), Some(Piece::WKnight));
+    assert!(!promotion.is_capture());
+
+    let capture = pack("b7a8q");
+    assert_eq!(capture.promotion(), Some(Piece::WQueen));
+    assert!(capture.is_capture());
+    assert!(!pack("e1f1").is_capture());
+
+    assert_eq!(size_of::<PackedMove>(), 2);
+}
+
+#[test]
+fn test_move_list() {
+    let mut list = MoveList::<u16>::new();
+    assert!(list.is_empty());
+
+    for x in [5, 1, 4, 1, 3] {
+        list.push(x);
+    }
+    assert_eq!(&list[..], [5, 1, 4, 1, 3]);
+
+    assert_eq!(list.swap_remove(1), 1);
+    assert_eq!(&list[..], [5, 3, 4, 1]);
+    assert_eq!(list.swap_remove(3), 1);
+    assert_eq!(&list[..], [5, 3, 4]);
+
+    list.sort_by_score(i32::from);
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), [5, 4, 3]);
+}
+
+#[test]
+fn test_move_list_sort_is_stable() {
+    let mut list = (0..10u16).collect::<MoveList<u16>>();
+    list.sort_by_score(|x| i32::from(x % 3));
+
+    assert_eq!(&list[..], [2, 5, 8, 1, 4, 7, 0, 3, 6, 9]);
+}
+
+#[test]
+fn test_move_list_holds_max_moves() {
+    // 218 legal moves, the most known for any position
+    let moves = moves_of("R6R/3Q4/1Q4Q1/4Q3/2Q4Q/Q4Q2/pp1Q4/kBNN1KB1 w - - 0 1");
+    assert_eq!(moves.len(), 218);
+
+    let mut list = MoveList::<u8>::new();
+    for i in 0..MAX_MOVES {
+        list.push(i as u8);
+    }
+    assert_eq!(list.len(), MAX_MOVES);
+
+    list.push(0);
+    assert_eq!(list.len(), MAX_MOVES);
+}
+
+#[test]
+fn test_overcrowded_position() {
+    // Far more queens than a game can have, with more legal moves than fit in a list
+    let mut board =
+        Board::from_str("Q1QQQQQk/Q5QR/Q5Q1/1Q5Q/Q4Q1Q/Q6Q/Q6Q/KQQQQQQB w - - 0 1").unwrap();
+    let moves = board.generate_moves();
+    assert_eq!(moves.len(), MAX_MOVES);
+
+    for m in &moves {
+        board.see_value(m);
+    }
+    board.perft(2);
+}
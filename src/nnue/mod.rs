@@ -0,0 +1,283 @@
+mod simd;
+#[cfg(test)]
+mod test;
+
+// An efficiently updatable network: 768 inputs, one per piece type, color and square, into a
+// hidden layer of N neurons, into one output. Both sides get their own accumulator of the
+// hidden layer, seen from their side of the board, and the output looks at the side to move's
+// half first.
+//
+// Network file format, all little-endian, nothing before or after:
+//
+//   bytes      field
+//   4          magic, "TNNU"
+//   4          u32 version, 1
+//   4          u32 hidden size N, a non-zero multiple of 16 of at most 2048
+//   768*N*2    i16 feature weights, the weights of feature f at f*N..(f+1)*N
+//   N*2        i16 feature biases
+//   2*N*2      i16 output weights, the side to move's half first
+//   4          i32 output bias
+//
+// Feature f is (relative color * 6 + piece type) * 64 + square. The relative color is 0 for
+// the accumulator's own pieces, the piece types go pawn, knight, bishop, rook, queen, king and
+// the square is A1 = 0 to H8 = 63, flipped top to bottom for black.
+//
+// Quantization: a hidden neuron at QA (255) is 1.0 and is clamped to 0..=QA, output weights are
+// scaled by QB (64) and the output bias by QA * QB. The output times SCALE (400) / (QA * QB) is
+// the evaluation in centipawns from the side to move's point of view.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::board::{Board, castling_rook_squares};
+use crate::movegen::Move;
+use crate::search::{MATE_BOUND, Score};
+use crate::types::{Color, Piece, Square};
+
+pub const INPUTS: usize = 768;
+pub const MAX_HIDDEN: usize = 2048;
+
+const MAGIC: &[u8; 4] = b"TNNU";
+const VERSION: u32 = 1;
+
+const QA: i16 = 255;
+const QB: i64 = 64;
+const SCALE: i64 = 400;
+
+#[derive(Debug)]
+pub enum NetworkError {
+    Io(io::Error),
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u32),
+    BadHiddenSize(u32),
+    TrailingData,
+}
+
+impl From<io::Error> for NetworkError {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            Self::Truncated
+        } else {
+            Self::Io(e)
+        }
+    }
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::Truncated => f.write_str("Network file is truncated"),
+            Self::BadMagic => f.write_str("Not a network file, bad magic"),
+            Self::UnsupportedVersion(v) => write!(f, "Unsupported network version {v}"),
+            Self::BadHiddenSize(n) => write!(
+                f,
+                "Hidden size {n} is not a non-zero multiple of {} of at most {MAX_HIDDEN}",
+                simd::CHUNK
+            ),
+            Self::TrailingData => f.write_str("Network file has data past the end"),
+        }
+    }
+}
+
+impl Error for NetworkError {}
+
+pub struct Network {
+    hidden: usize,
+    feature_weights: Vec<i16>,
+    feature_biases: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i32,
+}
+
+fn read_u32(input: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_i16s(input: &mut impl Read, len: usize) -> io::Result<Vec<i16>> {
+    let mut bytes = vec![0; len * 2];
+    input.read_exact(&mut bytes)?;
+
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect())
+}
+
+impl Network {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NetworkError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn read(mut input: impl Read) -> Result<Self, NetworkError> {
+        let mut magic = [0; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(NetworkError::BadMagic);
+        }
+
+        let version = read_u32(&mut input)?;
+        if version != VERSION {
+            return Err(NetworkError::UnsupportedVersion(version));
+        }
+
+        let hidden = read_u32(&mut input)?;
+        if hidden == 0
+            || hidden as usize > MAX_HIDDEN
+            || !(hidden as usize).is_multiple_of(simd::CHUNK)
+        {
+            return Err(NetworkError::BadHiddenSize(hidden));
+        }
+        let hidden = hidden as usize;
+
+        let network = Self {
+            hidden,
+            feature_weights: read_i16s(&mut input, INPUTS * hidden)?,
+            feature_biases: read_i16s(&mut input, hidden)?,
+            output_weights: read_i16s(&mut input, 2 * hidden)?,
+            output_bias: read_u32(&mut input)? as i32,
+        };
+
+        if input.read(&mut [0])? != 0 {
+            return Err(NetworkError::TrailingData);
+        }
+
+        Ok(network)
+    }
+
+    fn weights(&self, feature: usize) -> &[i16] {
+        &self.feature_weights[feature * self.hidden..(feature + 1) * self.hidden]
+    }
+
+    // From scratch, without keeping an accumulator around
+    pub fn evaluate(&self, board: &Board) -> Score {
+        Nnue::new(self, board).evaluate(board.side_to_move)
+    }
+}
+
+// The input `piece` on `square` is, as seen by `view`
+fn feature(view: Color, piece: Piece, square: Square) -> usize {
+    let relative_color = usize::from(piece.color() != view);
+    // Flips the rank for black
+    let square = if view == Color::WHITE {
+        square.as_int()
+    } else {
+        square.as_int() ^ 0x38
+    } as usize;
+
+    (relative_color * 6 + piece.to_index() % 6) * 64 + square
+}
+
+// The hidden layer before activation, once from each side, indexed by `Color::index`
+#[derive(Clone)]
+struct Accumulator([Vec<i16>; 2]);
+
+impl Accumulator {
+    fn update(&mut self, network: &Network, piece: Piece, square: Square, add: bool) {
+        for view in [Color::WHITE, Color::BLACK] {
+            let weights = network.weights(feature(view, piece, square));
+            if add {
+                simd::add(&mut self.0[view.index()], weights);
+            } else {
+                simd::sub(&mut self.0[view.index()], weights);
+            }
+        }
+    }
+}
+
+// A stack of accumulators that follows the board through make and unmake, so every move only
+// touches the few inputs it changes instead of all of them
+pub struct Nnue<'a> {
+    network: &'a Network,
+    stack: Vec<Accumulator>,
+    len: usize,
+}
+
+impl<'a> Nnue<'a> {
+    pub fn new(network: &'a Network, board: &Board) -> Self {
+        let mut nnue = Self {
+            network,
+            stack: vec![Accumulator([
+                network.feature_biases.clone(),
+                network.feature_biases.clone(),
+            ])],
+            len: 1,
+        };
+
+        nnue.refresh(board);
+        nnue
+    }
+
+    // Starts over from `board`, forgetting every pushed move
+    pub fn refresh(&mut self, board: &Board) {
+        self.len = 1;
+
+        let accumulator = &mut self.stack[0];
+        for view in &mut accumulator.0 {
+            view.copy_from_slice(&self.network.feature_biases);
+        }
+
+        let mut occupied = board.color_bb[0] | board.color_bb[1];
+        while !occupied.is_empty() {
+            let square = occupied.pop_lsb();
+            let piece = board.mailbox[square.as_int() as usize];
+            accumulator.update(self.network, piece, square, true);
+        }
+    }
+
+    // Must be called with the board `m` is about to be played on, right before `make_move`
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn push(&mut self, board: &Board, m: &Move) {
+        if self.len == self.stack.len() {
+            self.stack.push(self.stack[self.len - 1].clone());
+        }
+
+        let (done, rest) = self.stack.split_at_mut(self.len);
+        let accumulator = &mut rest[0];
+        for (view, parent) in accumulator.0.iter_mut().zip(&done[self.len - 1].0) {
+            view.copy_from_slice(parent);
+        }
+        self.len += 1;
+
+        let network = self.network;
+        accumulator.update(network, m.moved, m.from, false);
+        accumulator.update(network, m.promotion.unwrap_or(m.moved), m.to, true);
+
+        if m.captured != Piece::None {
+            let square = Board::captured_square(*m, board.state.ep_square);
+            accumulator.update(network, m.captured, square, false);
+        }
+
+        if m.is_castling {
+            let rook = Piece::WRook.with_color(m.moved.color());
+            let (from, to) = castling_rook_squares(m.to);
+            accumulator.update(network, rook, from, false);
+            accumulator.update(network, rook, to, true);
+        }
+    }
+
+    // Goes with `unmake_move`
+    pub fn pop(&mut self) {
+        debug_assert!(self.len > 1, "popped past the root");
+        self.len -= 1;
+    }
+
+    pub fn evaluate(&self, side_to_move: Color) -> Score {
+        let accumulator = &self.stack[self.len - 1].0;
+        let (ours, theirs) = self.network.output_weights.split_at(self.network.hidden);
+
+        let output = simd::crelu_dot(&accumulator[side_to_move.index()], ours)
+            + simd::crelu_dot(&accumulator[(!side_to_move).index()], theirs)
+            + i64::from(self.network.output_bias);
+
+        // A network must never claim a mate
+        (output * SCALE / (i64::from(QA) * QB))
+            .clamp(i64::from(-MATE_BOUND + 1), i64::from(MATE_BOUND - 1)) as Score
+    }
+}
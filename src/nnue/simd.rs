@@ -0,0 +1,158 @@
+use cfg_if::cfg_if;
+
+use super::QA;
+
+// Every slice passed in is a multiple of this long, which the network loader makes sure of
+pub(super) const CHUNK: usize = 16;
+
+// The reference everything else has to agree with, and what is used without SIMD
+#[allow(dead_code)]
+pub(super) mod scalar {
+    use super::QA;
+
+    pub fn add(acc: &mut [i16], weights: &[i16]) {
+        for (a, w) in acc.iter_mut().zip(weights) {
+            *a = a.wrapping_add(*w);
+        }
+    }
+
+    pub fn sub(acc: &mut [i16], weights: &[i16]) {
+        for (a, w) in acc.iter_mut().zip(weights) {
+            *a = a.wrapping_sub(*w);
+        }
+    }
+
+    // Sum of clamp(acc, 0, QA) * weight
+    pub fn crelu_dot(acc: &[i16], weights: &[i16]) -> i64 {
+        acc.iter()
+            .zip(weights)
+            .map(|(&a, &w)| i64::from(i32::from(a.clamp(0, QA)) * i32::from(w)))
+            .sum()
+    }
+}
+
+// The SIMD dot products add pairs of products into i32 lanes. Each pair is at most
+// 2 * QA * 2^15 in size, so a lane takes this many before it is moved into an i64.
+#[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+const PAIRS_PER_LANE: usize = 128;
+
+cfg_if! {
+    if #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))] {
+        use std::arch::x86_64::{
+            __m256i, _mm256_add_epi16, _mm256_add_epi32, _mm256_loadu_si256, _mm256_madd_epi16,
+            _mm256_max_epi16, _mm256_min_epi16, _mm256_set1_epi16, _mm256_setzero_si256,
+            _mm256_storeu_si256, _mm256_sub_epi16,
+        };
+
+        pub(super) fn add(acc: &mut [i16], weights: &[i16]) {
+            for (a, w) in acc.chunks_exact_mut(CHUNK).zip(weights.chunks_exact(CHUNK)) {
+                unsafe {
+                    let sum = _mm256_add_epi16(
+                        _mm256_loadu_si256(a.as_ptr().cast()),
+                        _mm256_loadu_si256(w.as_ptr().cast()),
+                    );
+                    _mm256_storeu_si256(a.as_mut_ptr().cast(), sum);
+                }
+            }
+        }
+
+        pub(super) fn sub(acc: &mut [i16], weights: &[i16]) {
+            for (a, w) in acc.chunks_exact_mut(CHUNK).zip(weights.chunks_exact(CHUNK)) {
+                unsafe {
+                    let difference = _mm256_sub_epi16(
+                        _mm256_loadu_si256(a.as_ptr().cast()),
+                        _mm256_loadu_si256(w.as_ptr().cast()),
+                    );
+                    _mm256_storeu_si256(a.as_mut_ptr().cast(), difference);
+                }
+            }
+        }
+
+        pub(super) fn crelu_dot(acc: &[i16], weights: &[i16]) -> i64 {
+            let (zero, max) = unsafe { (_mm256_setzero_si256(), _mm256_set1_epi16(QA)) };
+            // A chunk gives every lane one pair
+            let block = PAIRS_PER_LANE * CHUNK;
+
+            let mut total = 0;
+            for (acc, weights) in acc.chunks(block).zip(weights.chunks(block)) {
+                let mut sum = zero;
+                for (a, w) in acc.chunks_exact(CHUNK).zip(weights.chunks_exact(CHUNK)) {
+                    unsafe {
+                        let a = _mm256_min_epi16(
+                            _mm256_max_epi16(_mm256_loadu_si256(a.as_ptr().cast()), zero),
+                            max,
+                        );
+                        let products = _mm256_madd_epi16(a, _mm256_loadu_si256(w.as_ptr().cast()));
+                        sum = _mm256_add_epi32(sum, products);
+                    }
+                }
+
+                total += unsafe { std::mem::transmute::<__m256i, [i32; 8]>(sum) }
+                    .into_iter()
+                    .map(i64::from)
+                    .sum::<i64>();
+            }
+
+            total
+        }
+    } else if #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))] {
+        use std::arch::x86_64::{
+            __m128i, _mm_add_epi16, _mm_add_epi32, _mm_loadu_si128, _mm_madd_epi16,
+            _mm_max_epi16, _mm_min_epi16, _mm_set1_epi16, _mm_setzero_si128, _mm_storeu_si128,
+            _mm_sub_epi16,
+        };
+
+        // Half a chunk per register
+        const LANES: usize = CHUNK / 2;
+
+        pub(super) fn add(acc: &mut [i16], weights: &[i16]) {
+            for (a, w) in acc.chunks_exact_mut(LANES).zip(weights.chunks_exact(LANES)) {
+                unsafe {
+                    let sum = _mm_add_epi16(
+                        _mm_loadu_si128(a.as_ptr().cast()),
+                        _mm_loadu_si128(w.as_ptr().cast()),
+                    );
+                    _mm_storeu_si128(a.as_mut_ptr().cast(), sum);
+                }
+            }
+        }
+
+        pub(super) fn sub(acc: &mut [i16], weights: &[i16]) {
+            for (a, w) in acc.chunks_exact_mut(LANES).zip(weights.chunks_exact(LANES)) {
+                unsafe {
+                    let difference = _mm_sub_epi16(
+                        _mm_loadu_si128(a.as_ptr().cast()),
+                        _mm_loadu_si128(w.as_ptr().cast()),
+                    );
+                    _mm_storeu_si128(a.as_mut_ptr().cast(), difference);
+                }
+            }
+        }
+
+        pub(super) fn crelu_dot(acc: &[i16], weights: &[i16]) -> i64 {
+            let (zero, max) = unsafe { (_mm_setzero_si128(), _mm_set1_epi16(QA)) };
+            let block = PAIRS_PER_LANE * LANES;
+
+            let mut total = 0;
+            for (acc, weights) in acc.chunks(block).zip(weights.chunks(block)) {
+                let mut sum = zero;
+                for (a, w) in acc.chunks_exact(LANES).zip(weights.chunks_exact(LANES)) {
+                    unsafe {
+                        let a = _mm_min_epi16(_mm_max_epi16(_mm_loadu_si128(a.as_ptr().cast()), zero), max);
+                        let products = _mm_madd_epi16(a, _mm_loadu_si128(w.as_ptr().cast()));
+                        sum = _mm_add_epi32(sum, products);
+                    }
+                }
+
+                total += unsafe { std::mem::transmute::<__m128i, [i32; 4]>(sum) }
+                    .into_iter()
+                    .map(i64::from)
+                    .sum::<i64>();
+            }
+
+            total
+        }
+    } else {
+        pub(super) use self::scalar::{add, crelu_dot, sub};
+    }
+}
@@ -0,0 +1,250 @@
+#[cfg(test)]
+use super::*;
+use crate::search::{Limits, Search, tt::TranspositionTable};
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+
+const POSITIONS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "8/8/8/KPp4r/1R3p1k/8/4P1P1/8 w - c6 0 2",
+];
+
+// Little-endian bytes of a network laid out as in the format spec
+fn network_bytes(
+    hidden: usize,
+    feature_weights: &[i16],
+    feature_biases: &[i16],
+    output_weights: &[i16],
+    output_bias: i32,
+) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(VERSION.to_le_bytes());
+    bytes.extend((hidden as u32).to_le_bytes());
+    for w in feature_weights
+        .iter()
+        .chain(feature_biases)
+        .chain(output_weights)
+    {
+        bytes.extend(w.to_le_bytes());
+    }
+    bytes.extend(output_bias.to_le_bytes());
+
+    bytes
+}
+
+fn random_weights(len: usize, seed: &mut u64, range: i16) -> Vec<i16> {
+    (0..len)
+        .map(|_| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            ((*seed % (2 * range as u64 + 1)) as i32 - i32::from(range)) as i16
+        })
+        .collect()
+}
+
+fn random_network(hidden: usize) -> Network {
+    let mut seed = 0x9e37_79b9_7f4a_7c15;
+    let bytes = network_bytes(
+        hidden,
+        &random_weights(INPUTS * hidden, &mut seed, 64),
+        &random_weights(hidden, &mut seed, 64),
+        &random_weights(2 * hidden, &mut seed, 64),
+        1234,
+    );
+
+    Network::read(bytes.as_slice()).unwrap()
+}
+
+#[test]
+fn test_known_output() {
+    // Neuron 0 counts our queens and neuron 1 theirs, so the output is the queen difference
+    let hidden = 16;
+    let mut weights = vec![0; INPUTS * hidden];
+    for square in 0..64 {
+        weights[(4 * 64 + square) * hidden] = QA;
+        weights[(10 * 64 + square) * hidden + 1] = QA;
+    }
+    let mut output = vec![0; 2 * hidden];
+    output[0] = 144;
+    output[1] = -144;
+
+    let bytes = network_bytes(hidden, &weights, &[0; 16], &output, 0);
+    let network = Network::read(bytes.as_slice()).unwrap();
+
+    let eval = |fen| network.evaluate(&Board::from_str(fen).unwrap());
+    assert_eq!(eval("4k3/8/8/8/8/8/8/3QK3 w - - 0 1"), 900);
+    assert_eq!(eval("4k3/8/8/8/8/8/8/3QK3 b - - 0 1"), -900);
+    assert_eq!(eval("3qk3/8/8/8/8/8/8/3QK3 w - - 0 1"), 0);
+}
+
+#[test]
+fn test_perspective_symmetry() {
+    let network = random_network(32);
+    let eval = |fen| network.evaluate(&Board::from_str(fen).unwrap());
+
+    // Each position next to itself with the colors swapped and the board flipped
+    assert_eq!(
+        eval(POSITIONS[0]),
+        eval("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1")
+    );
+    assert_eq!(
+        eval("4k3/8/8/8/8/8/1P6/3QK3 w - - 0 1"),
+        eval("3qk3/1p6/8/8/8/8/8/4K3 b - - 0 1")
+    );
+}
+
+fn assert_incremental(nnue: &mut Nnue, board: &mut Board, depth: u32) {
+    let fresh = Nnue::new(nnue.network, board);
+    for (incremental, refreshed) in nnue.stack[nnue.len - 1].0.iter().zip(&fresh.stack[0].0) {
+        assert_eq!(
+            incremental,
+            refreshed,
+            "Accumulator mismatch at {}",
+            board.to_fen()
+        );
+    }
+    assert_eq!(
+        nnue.evaluate(board.side_to_move),
+        fresh.evaluate(board.side_to_move)
+    );
+
+    if depth == 0 {
+        return;
+    }
+
+    for m in &board.generate_moves() {
+        nnue.push(board, m);
+        board.make_move(m);
+        assert_incremental(nnue, board, depth - 1);
+        board.unmake_move();
+        nnue.pop();
+    }
+}
+
+#[test]
+fn test_incremental_matches_refresh() {
+    let network = random_network(32);
+
+    // Between them these have castling, en passant, promotions and captures of all kinds
+    for fen in POSITIONS {
+        let mut board = Board::from_str(fen).unwrap();
+        let mut nnue = Nnue::new(&network, &board);
+        assert_incremental(&mut nnue, &mut board, 2);
+    }
+}
+
+#[test]
+fn test_simd_matches_scalar() {
+    let mut seed = 42;
+    let acc = random_weights(64, &mut seed, 600);
+    let weights = random_weights(64, &mut seed, i16::MAX);
+
+    assert_eq!(
+        simd::crelu_dot(&acc, &weights),
+        simd::scalar::crelu_dot(&acc, &weights)
+    );
+
+    let (mut fast, mut slow) = (acc.clone(), acc);
+    simd::add(&mut fast, &weights);
+    simd::scalar::add(&mut slow, &weights);
+    assert_eq!(fast, slow);
+
+    simd::sub(&mut fast, &weights);
+    simd::sub(&mut fast, &weights);
+    simd::scalar::sub(&mut slow, &weights);
+    simd::scalar::sub(&mut slow, &weights);
+    assert_eq!(fast, slow);
+}
+
+#[test]
+fn test_extreme_weights() {
+    // As far from zero as the format allows, over the widest hidden layer
+    let acc = vec![QA; MAX_HIDDEN];
+    for weight in [i16::MAX, i16::MIN] {
+        let weights = vec![weight; MAX_HIDDEN];
+        let expected = MAX_HIDDEN as i64 * i64::from(QA) * i64::from(weight);
+
+        assert_eq!(simd::crelu_dot(&acc, &weights), expected);
+        assert_eq!(simd::scalar::crelu_dot(&acc, &weights), expected);
+    }
+
+    // Every neuron at its clamp and every output weight at its limit
+    let hidden = 512;
+    let bytes = network_bytes(
+        hidden,
+        &vec![0; INPUTS * hidden],
+        &vec![QA; hidden],
+        &vec![i16::MAX; 2 * hidden],
+        i32::MAX,
+    );
+    let network = Network::read(bytes.as_slice()).unwrap();
+    assert_eq!(network.evaluate(&Board::new_starting()), MATE_BOUND - 1);
+}
+
+#[test]
+fn test_bad_files() {
+    let read = |bytes: &[u8]| Network::read(bytes).err().unwrap().to_string();
+    let good = network_bytes(16, &vec![0; INPUTS * 16], &[0; 16], &[0; 32], 0);
+
+    assert!(Network::read(good.as_slice()).is_ok());
+    assert_eq!(read(b"TNN"), NetworkError::Truncated.to_string());
+    assert_eq!(
+        read(&good[..good.len() - 1]),
+        NetworkError::Truncated.to_string()
+    );
+    assert_eq!(
+        read(&[good.as_slice(), &[0]].concat()),
+        NetworkError::TrailingData.to_string()
+    );
+
+    let mut bad = good.clone();
+    bad[0] = b'X';
+    assert_eq!(read(&bad), NetworkError::BadMagic.to_string());
+
+    let mut bad = good.clone();
+    bad[4] = 2;
+    assert_eq!(read(&bad), NetworkError::UnsupportedVersion(2).to_string());
+
+    let mut bad = good;
+    bad[8] = 24;
+    assert_eq!(read(&bad), NetworkError::BadHiddenSize(24).to_string());
+}
+
+#[test]
+fn test_search_with_network() {
+    // Neuron 0 is our material and neuron 1 theirs, in units of 16 centipawns
+    let hidden = 16;
+    let values = [6, 20, 20, 31, 56, 0];
+    let mut weights = vec![0; INPUTS * hidden];
+    for (piece, value) in values.into_iter().enumerate() {
+        for square in 0..64 {
+            weights[(piece * 64 + square) * hidden] = value;
+            weights[((piece + 6) * 64 + square) * hidden + 1] = value;
+        }
+    }
+    let mut output = vec![0; 2 * hidden];
+    output[0] = 653;
+    output[1] = -653;
+
+    let bytes = network_bytes(hidden, &weights, &[0; 16], &output, 0);
+    let network = Network::read(bytes.as_slice()).unwrap();
+    let stop = AtomicBool::new(false);
+
+    let result = Search::new(
+        Board::from_str("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1").unwrap(),
+        Limits {
+            depth: Some(4),
+            ..Default::default()
+        },
+        &stop,
+        &TranspositionTable::new(1),
+    )
+    .with_network(&network)
+    .run(|_| {});
+
+    assert_eq!(result.best_move.unwrap().to_string(), "d1d5");
+    assert!(result.score > 400);
+}
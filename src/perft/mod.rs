@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod test;
+
+use crate::board::Board;
+use crate::movegen::Move;
+
+impl Board {
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.generate_moves();
+        // Legal move generation, so the leaves don't need to be played
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut nodes = 0;
+        for m in &moves {
+            self.make_move(m);
+            nodes += self.perft(depth - 1);
+            self.unmake_move();
+        }
+
+        nodes
+    }
+
+    // Node count under each root move
+    pub fn divide(&mut self, depth: u32) -> Vec<(Move, u64)> {
+        let depth = depth.max(1);
+
+        self.generate_moves()
+            .into_iter()
+            .map(|m| {
+                self.make_move(&m);
+                let nodes = self.perft(depth - 1);
+                self.unmake_move();
+
+                (m, nodes)
+            })
+            .collect()
+    }
+}
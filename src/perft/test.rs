@@ -0,0 +1,88 @@
+#[cfg(test)]
+use super::*;
+use std::str::FromStr;
+
+// Positions with their expected perft results, one per line: `<fen> ;D1 20 ;D2 400 ...`
+const SUITE: &str = include_str!("perft.epd");
+
+// Depths with more nodes than this only run with `cargo test --release -- --ignored`
+const FAST_NODE_LIMIT: u64 = 200_000;
+
+fn parse_suite() -> Vec<(&'static str, Vec<(u32, u64)>)> {
+    SUITE
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.split(';');
+            let fen = fields.next().unwrap().trim();
+
+            let depths = fields
+                .map(|field| {
+                    let (depth, nodes) = field
+                        .trim()
+                        .strip_prefix('D')
+                        .and_then(|x| x.split_once(' '))
+                        .unwrap_or_else(|| panic!("Malformed EPD field '{field}' in: {line}"));
+
+                    (depth.parse().unwrap(), nodes.trim().parse().unwrap())
+                })
+                .collect();
+
+            (fen, depths)
+        })
+        .collect()
+}
+
+fn run_suite(node_limit: u64) {
+    let mut failures = Vec::new();
+
+    for (fen, depths) in parse_suite() {
+        let mut board = Board::from_str(fen).unwrap_or_else(|e| panic!("{fen}: {e}"));
+
+        for (depth, expected) in depths {
+            if expected > node_limit {
+                continue;
+            }
+
+            let nodes = board.perft(depth);
+            if nodes != expected {
+                failures.push(format!("{fen} D{depth}: expected {expected}, got {nodes}"));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Perft mismatches:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_suite_parses() {
+    let suite = parse_suite();
+
+    assert!(!suite.is_empty());
+    assert!(suite.iter().all(|(_, depths)| !depths.is_empty()));
+}
+
+#[test]
+fn test_perft_suite() {
+    run_suite(FAST_NODE_LIMIT);
+}
+
+#[test]
+#[ignore = "deep perft, run with --release -- --ignored"]
+fn test_perft_suite_deep() {
+    run_suite(u64::MAX);
+}
+
+#[test]
+fn test_divide_sums_to_perft() {
+    let mut board = Board::new_starting();
+    let divide = board.divide(3);
+
+    assert_eq!(divide.len(), 20);
+    assert_eq!(divide.iter().map(|(_, n)| n).sum::<u64>(), board.perft(3));
+}
@@ -0,0 +1,448 @@
+#[cfg(test)]
+mod test;
+pub mod tt;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use self::tt::{Bound, TranspositionTable, score_from_tt, score_to_tt};
+use crate::board::{Board, SEE_VALUES};
+use crate::eval::{PawnTable, evaluate_cached};
+use crate::movegen::{Move, PackedMove};
+use crate::nnue::{Network, Nnue};
+use crate::types::Piece;
+
+pub type Score = i32;
+
+pub const MAX_PLY: usize = 128;
+pub const MATE: Score = 32_000;
+pub const INFINITY: Score = MATE + 1;
+
+// Any score past this is a forced mate
+pub(crate) const MATE_BOUND: Score = MATE - MAX_PLY as Score;
+
+// How many nodes to search between looking at the clock and the stop flag
+const CHECK_INTERVAL: u64 = 1024;
+
+// Captures that lose material go after the quiet moves
+const LOSING_CAPTURE: Score = 100_000;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+    // Only `stop` ends the search, it waits for it even after searching as deep as it can
+    pub infinite: bool,
+}
+
+// Sent after every completed iteration
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub depth: u32,
+    pub score: Score,
+    pub nodes: u64,
+    pub time: Duration,
+    pub hashfull: usize,
+    pub pv: Vec<Move>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct SearchResult {
+    // None only if there are no legal moves
+    pub best_move: Option<Move>,
+    pub score: Score,
+    pub depth: u32,
+    pub nodes: u64,
+    pub pv: Vec<Move>,
+}
+
+// Moves until mate for mate scores, negative if we are getting mated
+pub const fn mate_in(score: Score) -> Option<Score> {
+    if score >= MATE_BOUND {
+        Some((MATE - score + 1) / 2)
+    } else if score <= -MATE_BOUND {
+        Some(-(MATE + score) / 2)
+    } else {
+        None
+    }
+}
+
+pub struct Search<'a> {
+    board: Board,
+    limits: Limits,
+    stop: &'a AtomicBool,
+    tt: &'a TranspositionTable,
+    pawn_table: PawnTable,
+    // Evaluates instead of the hand written eval when set
+    nnue: Option<Nnue<'a>>,
+    // Set while searching the opponent's time, see `with_ponder`
+    ponder: Option<&'a AtomicBool>,
+    start: Instant,
+    nodes: u64,
+    aborted: bool,
+    // PV of the last completed iteration, searched first in the next one
+    previous_pv: Vec<Move>,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(
+        board: Board,
+        limits: Limits,
+        stop: &'a AtomicBool,
+        tt: &'a TranspositionTable,
+    ) -> Self {
+        Self {
+            board,
+            limits,
+            stop,
+            tt,
+            pawn_table: PawnTable::new(),
+            nnue: None,
+            ponder: None,
+            start: Instant::now(),
+            nodes: 0,
+            aborted: false,
+            previous_pv: Vec::new(),
+        }
+    }
+
+    pub fn with_network(mut self, network: &'a Network) -> Self {
+        self.nnue = Some(Nnue::new(network, &self.board));
+        self
+    }
+
+    // While `ponder` is set the search goes on like an infinite one. Once it is cleared the
+    // clock starts and the time limit applies as usual.
+    pub const fn with_ponder(mut self, ponder: &'a AtomicBool) -> Self {
+        self.ponder = Some(ponder);
+        self
+    }
+
+    // Iterative deepening until a limit is hit or `stop` is set. The result is always from
+    // the deepest completed iteration, a partial one is thrown away.
+    pub fn run(&mut self, report: impl FnMut(&Report)) -> SearchResult {
+        let result = self.iterate(report);
+
+        while (self.limits.infinite || self.pondering()) && !self.stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        result
+    }
+
+    fn iterate(&mut self, mut report: impl FnMut(&Report)) -> SearchResult {
+        self.start = Instant::now();
+        self.tt.new_search();
+
+        let mut result = SearchResult {
+            best_move: self.board.generate_moves().first().copied(),
+            ..Default::default()
+        };
+        if result.best_move.is_none() {
+            return result;
+        }
+
+        let max_depth = self
+            .limits
+            .depth
+            .unwrap_or(MAX_PLY as u32)
+            .clamp(1, MAX_PLY as u32 - 1);
+        for depth in 1..=max_depth {
+            let mut pv = Vec::new();
+            let score = self.negamax(depth as i32, 0, -INFINITY, INFINITY, &mut pv);
+            if self.aborted {
+                break;
+            }
+
+            result = SearchResult {
+                best_move: pv.first().copied(),
+                score,
+                depth,
+                nodes: self.nodes,
+                pv: pv.clone(),
+            };
+            report(&Report {
+                depth,
+                score,
+                nodes: self.nodes,
+                time: self.start.elapsed(),
+                hashfull: self.tt.hashfull(),
+                pv: pv.clone(),
+            });
+            self.previous_pv = pv;
+
+            // The next iteration would most likely not finish in time anyway
+            if self.limits.time.is_some_and(|t| self.elapsed() * 2 > t) {
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+        result
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if self.aborted {
+            return true;
+        }
+
+        if self.limits.nodes.is_some_and(|n| self.nodes >= n) {
+            self.aborted = true;
+        } else if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            self.aborted = self.stop.load(Ordering::Relaxed)
+                || self.limits.time.is_some_and(|t| self.elapsed() >= t);
+        }
+
+        self.aborted
+    }
+
+    fn pondering(&self) -> bool {
+        self.ponder.is_some_and(|p| p.load(Ordering::Relaxed))
+    }
+
+    // Time used against the time limit, which only starts running once pondering is over
+    fn elapsed(&mut self) -> Duration {
+        if self.pondering() {
+            self.start = Instant::now();
+        }
+
+        self.start.elapsed()
+    }
+
+    fn evaluate(&mut self) -> Score {
+        match &self.nnue {
+            Some(nnue) => nnue.evaluate(self.board.side_to_move),
+            None => evaluate_cached(&self.board, &mut self.pawn_table),
+        }
+    }
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn make_move(&mut self, m: &Move) {
+        if let Some(nnue) = &mut self.nnue {
+            nnue.push(&self.board, m);
+        }
+        self.board.make_move(m);
+    }
+
+    fn unmake_move(&mut self) {
+        self.board.unmake_move();
+        if let Some(nnue) = &mut self.nnue {
+            nnue.pop();
+        }
+    }
+
+    fn is_draw(&self) -> bool {
+        self.board.state.rule_50 >= 100 || self.board.is_repetition()
+    }
+
+    fn negamax(
+        &mut self,
+        mut depth: i32,
+        ply: usize,
+        mut alpha: Score,
+        beta: Score,
+        pv: &mut Vec<Move>,
+    ) -> Score {
+        pv.clear();
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        if ply > 0 && self.is_draw() {
+            return 0;
+        }
+
+        let in_check = !self.board.state.checkers.is_empty();
+        if in_check {
+            depth += 1;
+        }
+
+        if depth <= 0 {
+            return self.quiescence(ply, alpha, beta);
+        }
+        if ply >= MAX_PLY - 1 {
+            return self.evaluate();
+        }
+
+        // Cutting off at PV nodes would cut the PV short, and those are few anyway
+        let pv_node = beta - alpha > 1;
+        let key = self.board.hash();
+        let entry = self.tt.probe(key);
+        if let Some(entry) = entry
+            && !pv_node
+            && i32::from(entry.depth) >= depth
+        {
+            let score = score_from_tt(entry.score, ply);
+            let usable = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+
+            if usable {
+                return score;
+            }
+        }
+
+        let mut moves = self.board.generate_moves();
+        if moves.is_empty() {
+            return if in_check { -MATE + ply as Score } else { 0 };
+        }
+
+        let pv_move = self.previous_pv.get(ply).copied();
+        let tt_move = entry
+            .filter(|e| !e.best_move.is_null())
+            .map(|e| e.best_move.to_move(&self.board));
+        moves.sort_by_score(|m| order_score(&self.board, m, pv_move, tt_move));
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        let mut child_pv = Vec::new();
+        for (i, m) in moves.iter().enumerate() {
+            self.make_move(m);
+
+            // Everything after the first move only has to prove it is no better, which a null
+            // window does cheaply. Only if it turns out better is it searched again properly.
+            let mut score;
+            if i == 0 {
+                score = -self.negamax(depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            } else {
+                score = -self.negamax(depth - 1, ply + 1, -alpha - 1, -alpha, &mut child_pv);
+                if score > alpha && score < beta {
+                    score = -self.negamax(depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+                }
+            }
+
+            self.unmake_move();
+            if self.aborted {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                best_move = Some(*m);
+            }
+
+            if score > alpha {
+                alpha = score;
+
+                pv.clear();
+                pv.push(*m);
+                pv.extend_from_slice(&child_pv);
+
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        let bound = if best >= beta {
+            Bound::Lower
+        } else if best > original_alpha {
+            Bound::Exact
+        } else {
+            // Every move failed low, none of them is known to be best
+            best_move = None;
+            Bound::Upper
+        };
+        self.tt.store(
+            key,
+            best_move.map_or(PackedMove::NULL, |m| PackedMove::new(m, &self.board)),
+            depth.clamp(0, 255) as u8,
+            bound,
+            score_to_tt(best, ply),
+        );
+
+        best
+    }
+
+    // Only captures and promotions are searched, so the eval is never trusted in the middle of
+    // an exchange. The side to move can always "stand pat" on the static eval instead, except in
+    // check, where every evasion has to be looked at.
+    fn quiescence(&mut self, ply: usize, mut alpha: Score, beta: Score) -> Score {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        if ply >= MAX_PLY - 1 {
+            return self.evaluate();
+        }
+
+        let in_check = !self.board.state.checkers.is_empty();
+        let mut best = -INFINITY;
+        let mut moves = if in_check {
+            self.board.generate_moves()
+        } else {
+            best = self.evaluate();
+            if best >= beta {
+                return best;
+            }
+            alpha = alpha.max(best);
+
+            self.board.generate_captures()
+        };
+
+        if in_check && moves.is_empty() {
+            return -MATE + ply as Score;
+        }
+
+        moves.sort_by_score(|m| order_score(&self.board, m, None, None));
+
+        for m in &moves {
+            // Losing material can't raise the score above standing pat
+            if !in_check && !self.board.see(m, 0) {
+                continue;
+            }
+
+            self.make_move(m);
+            let score = -self.quiescence(ply + 1, -beta, -alpha);
+            self.unmake_move();
+
+            if self.aborted {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        best
+    }
+}
+
+// Search the last PV first, then the TT move, then captures by most valuable victim and least
+// valuable attacker, with the ones that lose material last
+fn order_score(board: &Board, m: Move, pv_move: Option<Move>, tt_move: Option<Move>) -> Score {
+    if pv_move == Some(m) {
+        return INFINITY;
+    }
+    if tt_move == Some(m) {
+        return INFINITY - 1;
+    }
+
+    let mut score = 0;
+    if m.captured != Piece::None {
+        score += 10 * SEE_VALUES[m.captured.to_index() % 6] - SEE_VALUES[m.moved.to_index() % 6];
+        if !board.see(&m, 0) {
+            score -= LOSING_CAPTURE;
+        }
+    }
+    if let Some(promotion) = m.promotion {
+        score += SEE_VALUES[promotion.to_index() % 6];
+    }
+
+    score
+}
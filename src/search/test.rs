@@ -0,0 +1,289 @@
+#[cfg(test)]
+use super::tt::Entry;
+use super::*;
+use crate::eval::evaluate;
+use std::str::FromStr;
+
+fn search(fen: &str, limits: Limits) -> SearchResult {
+    let stop = AtomicBool::new(false);
+    Search::new(
+        Board::from_str(fen).unwrap(),
+        limits,
+        &stop,
+        &TranspositionTable::new(1),
+    )
+    .run(|_| {})
+}
+
+fn search_depth(fen: &str, depth: u32) -> SearchResult {
+    search(
+        fen,
+        Limits {
+            depth: Some(depth),
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_mate_in_one() {
+    let result = search_depth("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+
+    assert_eq!(result.best_move.unwrap().to_string(), "a1a8");
+    assert_eq!(mate_in(result.score), Some(1));
+}
+
+#[test]
+fn test_mate_in_two() {
+    let result = search_depth("k7/8/2K5/8/8/8/8/7R w - - 0 1", 4);
+
+    assert_eq!(mate_in(result.score), Some(2));
+    assert_eq!(result.pv.len(), 3);
+}
+
+#[test]
+fn test_getting_mated() {
+    // Kb8 is forced, then Rh8 mates
+    let result = search_depth("k7/8/1K6/8/8/8/8/7R b - - 0 1", 3);
+
+    assert_eq!(mate_in(result.score), Some(-1));
+}
+
+#[test]
+fn test_wins_hanging_queen() {
+    let result = search_depth("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1", 2);
+
+    assert_eq!(result.best_move.unwrap().to_string(), "d1d5");
+    assert!(result.score > 400);
+}
+
+#[test]
+fn test_no_legal_moves() {
+    let checkmate = search_depth("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1", 3);
+    let stalemate = search_depth("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", 3);
+
+    assert_eq!(checkmate.best_move, None);
+    assert_eq!(stalemate.best_move, None);
+}
+
+#[test]
+fn test_reports_every_iteration() {
+    let stop = AtomicBool::new(false);
+    let limits = Limits {
+        depth: Some(4),
+        ..Default::default()
+    };
+
+    let mut depths = Vec::new();
+    let result = Search::new(
+        Board::new_starting(),
+        limits,
+        &stop,
+        &TranspositionTable::new(1),
+    )
+    .run(|report| {
+        assert!(!report.pv.is_empty());
+        depths.push(report.depth);
+    });
+
+    assert_eq!(depths, [1, 2, 3, 4]);
+    assert_eq!(result.depth, 4);
+    assert_eq!(result.best_move, result.pv.first().copied());
+}
+
+#[test]
+fn test_node_limit() {
+    let result = search(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        Limits {
+            nodes: Some(5000),
+            ..Default::default()
+        },
+    );
+
+    assert!(result.nodes <= 5000);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn test_stopped_search_still_moves() {
+    let stop = AtomicBool::new(true);
+    let result = Search::new(
+        Board::new_starting(),
+        Limits::default(),
+        &stop,
+        &TranspositionTable::new(1),
+    )
+    .run(|_| {});
+
+    assert_eq!(result.depth, 0);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn test_repetition_is_a_draw() {
+    // Down a queen, so going back to a position seen before is the best white can do
+    let mut board = Board::from_str("q3k3/8/8/8/8/8/8/4K1N1 w - - 0 1").unwrap();
+    for uci in ["g1f3", "e8d8", "f3g1", "d8e8", "g1f3", "e8d8"] {
+        let m = board.parse_uci_move(uci).unwrap();
+        board.make_move(&m);
+    }
+
+    let stop = AtomicBool::new(false);
+    let limits = Limits {
+        depth: Some(1),
+        ..Default::default()
+    };
+    let result = Search::new(board, limits, &stop, &TranspositionTable::new(1)).run(|_| {});
+
+    assert_eq!(result.best_move.unwrap().to_string(), "f3g1");
+    assert_eq!(result.score, 0);
+}
+
+#[test]
+fn test_no_poisoned_pawn_at_the_horizon() {
+    // Qxd5 wins a pawn at depth 1, until exd5 is looked at
+    let result = search_depth("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", 1);
+
+    assert_ne!(result.best_move.unwrap().to_string(), "d1d5");
+    assert!(result.score > 0);
+}
+
+#[test]
+fn test_quiescence() {
+    let stop = AtomicBool::new(false);
+    let tt = TranspositionTable::new(1);
+    let quiescence = |fen: &str| {
+        let board = Board::from_str(fen).unwrap();
+        Search::new(board, Limits::default(), &stop, &tt).quiescence(0, -INFINITY, INFINITY)
+    };
+
+    let eval = |fen: &str| evaluate(&Board::from_str(fen).unwrap());
+
+    // Stands pat instead of playing Qxd5
+    let fen = "4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1";
+    assert_eq!(quiescence(fen), eval(fen));
+    // Resolves the exchange on d5, where taking back would only lose black another rook
+    assert_eq!(
+        quiescence("4k3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1"),
+        eval("4k3/8/8/3R4/8/8/8/3RK3 w - - 0 1")
+    );
+    assert_eq!(
+        quiescence("4k3/3r4/8/3p4/8/8/3R4/3RK3 w - - 0 1"),
+        eval("4k3/3r4/8/3R4/8/8/8/3RK3 w - - 0 1")
+    );
+    // In check there is no standing pat, and no evasion is mate
+    assert_eq!(quiescence("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1"), -MATE);
+}
+
+// Keys that only differ in the low bits land in the same bucket
+const SAME_BUCKET: [u64; 3] = [0xabcd << 48 | 1, 0xabcd << 48 | 2, 0xabcd << 48 | 3];
+
+#[test]
+fn test_tt_store_and_probe() {
+    let tt = TranspositionTable::new(1);
+    let board = Board::new_starting();
+    let m = board.parse_uci_move("e2e4").unwrap();
+
+    assert_eq!(tt.probe(board.hash()), None);
+
+    tt.store(
+        board.hash(),
+        PackedMove::new(m, &board),
+        7,
+        Bound::Lower,
+        -123,
+    );
+    assert_eq!(
+        tt.probe(board.hash()),
+        Some(Entry {
+            best_move: PackedMove::new(m, &board),
+            depth: 7,
+            bound: Bound::Lower,
+            score: -123,
+        })
+    );
+
+    // A store without a move keeps the one that was there
+    tt.store(board.hash(), PackedMove::NULL, 8, Bound::Upper, 50);
+    let entry = tt.probe(board.hash()).unwrap();
+    assert_eq!(entry.best_move.to_move(&board), m);
+    assert_eq!(
+        (entry.depth, entry.bound, entry.score),
+        (8, Bound::Upper, 50)
+    );
+}
+
+#[test]
+fn test_tt_mate_scores_are_relative_to_the_node() {
+    // Mate in 3 plies from the root, found at ply 2, is mate in 1 ply from that node
+    let stored = tt::score_to_tt(MATE - 3, 2);
+    assert_eq!(stored, MATE - 1);
+    assert_eq!(tt::score_from_tt(stored, 4), MATE - 5);
+    assert_eq!(
+        tt::score_from_tt(tt::score_to_tt(-MATE + 6, 5), 5),
+        -MATE + 6
+    );
+    assert_eq!(tt::score_to_tt(250, 10), 250);
+}
+
+#[test]
+fn test_tt_replacement() {
+    let tt = TranspositionTable::new(1);
+    let [deep, shallow, newest] = SAME_BUCKET;
+
+    tt.store(deep, PackedMove::NULL, 10, Bound::Exact, 1);
+    tt.store(shallow, PackedMove::NULL, 2, Bound::Exact, 2);
+    tt.store(newest, PackedMove::NULL, 3, Bound::Exact, 3);
+
+    // The deep entry stays, the always-replace slot holds the newest one
+    assert_eq!(tt.probe(deep).unwrap().score, 1);
+    assert_eq!(tt.probe(shallow), None);
+    assert_eq!(tt.probe(newest).unwrap().score, 3);
+
+    // Entries from an earlier search get replaced no matter how deep they are
+    tt.new_search();
+    tt.store(shallow, PackedMove::NULL, 1, Bound::Exact, 4);
+    assert_eq!(tt.probe(deep), None);
+    assert_eq!(tt.probe(shallow).unwrap().score, 4);
+}
+
+#[test]
+fn test_tt_hashfull() {
+    let mut tt = TranspositionTable::new(1);
+    assert_eq!(tt.hashfull(), 0);
+
+    search_with_tt(Board::new_starting(), 5, &tt);
+    let full = tt.hashfull();
+    assert!(full > 0 && full <= 1000);
+
+    // Only the current search counts
+    tt.new_search();
+    assert_eq!(tt.hashfull(), 0);
+
+    search_with_tt(Board::new_starting(), 3, &tt);
+    tt.clear();
+    assert_eq!(tt.hashfull(), 0);
+}
+
+#[test]
+fn test_tt_is_shared_between_searches() {
+    let tt = TranspositionTable::new(1);
+    let board = Board::from_str("k7/8/2K5/8/8/8/8/7R w - - 0 1").unwrap();
+
+    let first = search_with_tt(board.clone(), 4, &tt);
+    let second = search_with_tt(board, 4, &tt);
+
+    assert_eq!(mate_in(second.score), Some(2));
+    assert!(second.nodes < first.nodes);
+}
+
+fn search_with_tt(board: Board, depth: u32, tt: &TranspositionTable) -> SearchResult {
+    let stop = AtomicBool::new(false);
+    let limits = Limits {
+        depth: Some(depth),
+        ..Default::default()
+    };
+
+    Search::new(board, limits, &stop, tt).run(|_| {})
+}
@@ -0,0 +1,198 @@
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+use super::{MATE_BOUND, Score};
+use crate::movegen::PackedMove;
+
+pub const DEFAULT_SIZE_MB: usize = 16;
+pub const MAX_SIZE_MB: usize = 1 << 16;
+
+// Which side of the window the score fell on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    // Failed high, the real score is at least this
+    Lower,
+    // Failed low, the real score is at most this
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    // Null if there is none
+    pub best_move: PackedMove,
+    pub depth: u8,
+    pub bound: Bound,
+    // Mate scores are relative to this node, not the root
+    pub score: Score,
+}
+
+// Mate scores are stored as the distance from the node instead of the root, so they stay
+// correct when the position is reached at a different ply
+pub const fn score_to_tt(score: Score, ply: usize) -> Score {
+    if score >= MATE_BOUND {
+        score + ply as Score
+    } else if score <= -MATE_BOUND {
+        score - ply as Score
+    } else {
+        score
+    }
+}
+
+pub const fn score_from_tt(score: Score, ply: usize) -> Score {
+    if score >= MATE_BOUND {
+        score - ply as Score
+    } else if score <= -MATE_BOUND {
+        score + ply as Score
+    } else {
+        score
+    }
+}
+
+// Data layout: move 0..16, score 16..32, depth 32..40, bound 40..42, age 42..48 and bit 48 set
+// so that no stored entry is ever zero, which is what an empty slot looks like. The key is
+// stored xored with the data, so a slot torn by two threads writing at once just fails to
+// match instead of returning another position's data.
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    fn load(&self) -> (u64, u64) {
+        let data = self.data.load(Ordering::Relaxed);
+        (self.key.load(Ordering::Relaxed) ^ data, data)
+    }
+
+    fn store(&self, key: u64, data: u64) {
+        self.key.store(key ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+}
+
+const fn depth_of(data: u64) -> u8 {
+    (data >> 32) as u8
+}
+
+const fn age_of(data: u64) -> u8 {
+    (data >> 42) as u8 & AGE_MASK
+}
+
+const AGE_MASK: u8 = 0x3f;
+const OCCUPIED: u64 = 1 << 48;
+
+// The first slot keeps the deepest entry of the current search, the second whatever came last
+#[derive(Default)]
+struct Bucket([Slot; 2]);
+
+pub struct TranspositionTable {
+    buckets: Vec<Bucket>,
+    age: AtomicU8,
+}
+
+impl TranspositionTable {
+    pub fn new(size_mb: usize) -> Self {
+        let len = (size_mb.clamp(1, MAX_SIZE_MB) << 20) / size_of::<Bucket>();
+
+        Self {
+            buckets: (0..len).map(|_| Bucket::default()).collect(),
+            age: AtomicU8::new(0),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.fill_with(Bucket::default);
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    // Called once per `go`, so entries from earlier searches get replaced first
+    pub fn new_search(&self) {
+        let age = (self.age.load(Ordering::Relaxed) + 1) & AGE_MASK;
+        self.age.store(age, Ordering::Relaxed);
+    }
+
+    fn bucket(&self, key: u64) -> &Bucket {
+        // Maps the key onto the table without needing a power of two length
+        &self.buckets[((u128::from(key) * self.buckets.len() as u128) >> 64) as usize]
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.bucket(key).0.iter().find_map(|slot| {
+            let (slot_key, data) = slot.load();
+            if slot_key != key || data == 0 {
+                return None;
+            }
+
+            Some(Entry {
+                best_move: PackedMove::from_u16(data as u16),
+                score: Score::from((data >> 16) as i16),
+                depth: depth_of(data),
+                bound: match (data >> 40) & 3 {
+                    0 => Bound::Exact,
+                    1 => Bound::Lower,
+                    _ => Bound::Upper,
+                },
+            })
+        })
+    }
+
+    pub fn store(&self, key: u64, best_move: PackedMove, depth: u8, bound: Bound, score: Score) {
+        let age = self.age.load(Ordering::Relaxed);
+        let [deepest, latest] = &self.bucket(key).0;
+
+        let (deepest_key, deepest_data) = deepest.load();
+        let slot = if deepest_data == 0
+            || deepest_key == key
+            || age_of(deepest_data) != age
+            || depth >= depth_of(deepest_data)
+        {
+            deepest
+        } else {
+            latest
+        };
+
+        // Keep the old move when the new entry has none, it is still the best guess we have
+        let mut best_move = best_move.as_u16();
+        let (slot_key, slot_data) = slot.load();
+        if best_move == PackedMove::NULL.as_u16() && slot_key == key {
+            best_move = slot_data as u16;
+        }
+
+        let bound = match bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        let data = u64::from(best_move)
+            | u64::from(score as i16 as u16) << 16
+            | u64::from(depth) << 32
+            | bound << 40
+            | u64::from(age) << 42
+            | OCCUPIED;
+
+        slot.store(key, data);
+    }
+
+    // Permille of the first thousand slots used by the current search
+    pub fn hashfull(&self) -> usize {
+        let age = self.age.load(Ordering::Relaxed);
+
+        self.buckets
+            .iter()
+            .take(500)
+            .flat_map(|bucket| &bucket.0)
+            .filter(|slot| {
+                let (_, data) = slot.load();
+                data != 0 && age_of(data) == age
+            })
+            .count()
+            * 1000
+            / (self.buckets.len().min(500) * 2)
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIZE_MB)
+    }
+}
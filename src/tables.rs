@@ -22,6 +22,128 @@ pub const RANK_MASKS: [Bitboard; 8] = {
     array
 };
 
+// The files next to each file
+pub const ADJACENT_FILE_MASKS: [Bitboard; 8] = {
+    let mut array = [Bitboard(0); 8];
+    let mut i = 0;
+    while i < 8 {
+        if i > 0 {
+            array[i].0 |= FILE_MASKS[i - 1].0;
+        }
+        if i < 7 {
+            array[i].0 |= FILE_MASKS[i + 1].0;
+        }
+        i += 1;
+    }
+
+    array
+};
+
+// FORWARD_RANKS_MASKS[color][rank] holds the ranks strictly in front of `rank`, as seen by `color`
+pub const FORWARD_RANKS_MASKS: [[Bitboard; 8]; 2] = {
+    let mut array = [[Bitboard(0); 8]; 2];
+    let mut i = 0;
+    while i < 8 {
+        let mut j = 0;
+        while j < 8 {
+            if j > i {
+                array[0][i].0 |= RANK_MASKS[j].0;
+            } else if j < i {
+                array[1][i].0 |= RANK_MASKS[j].0;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+
+    array
+};
+
+const SLIDING_STEPS: [(i8, i8); 8] = [
+    (0, 1),
+    (0, -1),
+    (1, 0),
+    (-1, 0),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+// BETWEEN_MASKS[a][b] holds the squares strictly between a and b if they share a rank, file or
+// diagonal, and is empty otherwise.
+pub static BETWEEN_MASKS: [[Bitboard; 64]; 64] = {
+    let mut array = [[Bitboard(0); 64]; 64];
+
+    let mut from = 0;
+    while from < 64 {
+        let mut d = 0;
+        while d < SLIDING_STEPS.len() {
+            let (df, dr) = SLIDING_STEPS[d];
+            let mut file = (from & 7) as i8 + df;
+            let mut rank = (from >> 3) as i8 + dr;
+            let mut between = 0u64;
+
+            while file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                let to = (rank * 8 + file) as usize;
+                array[from][to] = BB(between);
+                between |= 1 << to;
+
+                file += df;
+                rank += dr;
+            }
+
+            d += 1;
+        }
+
+        from += 1;
+    }
+
+    array
+};
+
+// LINE_MASKS[a][b] holds the whole edge to edge line going through a and b, including both, if they
+// are aligned, and is empty otherwise.
+pub static LINE_MASKS: [[Bitboard; 64]; 64] = {
+    let mut array = [[Bitboard(0); 64]; 64];
+
+    let mut from = 0;
+    while from < 64 {
+        let mut d = 0;
+        while d < SLIDING_STEPS.len() {
+            let (df, dr) = SLIDING_STEPS[d];
+            let mut line = 1u64 << from;
+
+            let mut sign = 1;
+            while sign >= -1 {
+                let mut file = (from & 7) as i8 + df * sign;
+                let mut rank = (from >> 3) as i8 + dr * sign;
+                while file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                    line |= 1 << (rank * 8 + file);
+                    file += df * sign;
+                    rank += dr * sign;
+                }
+
+                sign -= 2;
+            }
+
+            let mut file = (from & 7) as i8 + df;
+            let mut rank = (from >> 3) as i8 + dr;
+            while file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                array[from][(rank * 8 + file) as usize] = BB(line);
+                file += df;
+                rank += dr;
+            }
+
+            d += 1;
+        }
+
+        from += 1;
+    }
+
+    array
+};
+
 // pub const DIAG_MASK: [Bitboard; 15] = [
 //     Bitboard(0x8040_2010_0804_0201),
 //     Bitboard(0x0080_4020_1008_0402),
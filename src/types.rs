@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 use std::fmt::Display;
-use std::ops::{BitAnd, BitOr, BitXor, Not};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 use std::str::FromStr;
 
 use crate::tables::{FILE_MASKS, RANK_MASKS};
@@ -122,6 +122,11 @@ pub struct Color(pub bool);
 impl Color {
     pub const WHITE: Self = Self(false);
     pub const BLACK: Self = Self(true);
+
+    #[allow(clippy::cast_lossless)]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
 }
 
 #[allow(non_snake_case)]
@@ -190,6 +195,11 @@ impl Piece {
         Color(self.as_int().is_negative())
     }
 
+    pub const fn with_color(self, color: Color) -> Self {
+        let t = self.type_of() as i8;
+        unsafe { Self::from_int(if color.0 { -t } else { t }) }
+    }
+
     pub(crate) const fn to_index(self) -> usize {
         match self {
             Piece::Woo => 12,
@@ -261,6 +271,13 @@ impl Square {
         self.as_int() & 7
     }
 
+    // King moves from one square to the other
+    pub const fn distance(self, other: Self) -> u8 {
+        let ranks = self.rank().abs_diff(other.rank());
+        let files = self.file().abs_diff(other.file());
+        if ranks > files { ranks } else { files }
+    }
+
     pub const fn distance_from_edge(self, d: Direction) -> u8 {
         #[allow(clippy::enum_glob_use)]
         use Direction::*;
@@ -365,6 +382,24 @@ impl BitXor for Bitboard {
     }
 }
 
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
 impl Direction {
     pub const fn inverse(self) -> Self {
         unsafe { Self::from_int(-self.as_int()) }
@@ -383,6 +418,34 @@ impl Direction {
 }
 
 impl Bitboard {
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(self, s: Square) -> bool {
+        self.0 & (1u64 << s.as_int()) != 0
+    }
+
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub const fn lsb(self) -> Square {
+        debug_assert!(self.0 != 0);
+        unsafe { Square::from_int(self.0.trailing_zeros() as u8) }
+    }
+
+    pub const fn msb(self) -> Square {
+        debug_assert!(self.0 != 0);
+        unsafe { Square::from_int(63 - self.0.leading_zeros() as u8) }
+    }
+
+    pub const fn pop_lsb(&mut self) -> Square {
+        let s = self.lsb();
+        self.0 &= self.0 - 1;
+        s
+    }
+
     pub const fn shift_by_direction(self, d: Direction) -> Self {
         #[allow(clippy::enum_glob_use)]
         use Direction::*;
@@ -0,0 +1,330 @@
+#[cfg(test)]
+mod test;
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::board::Board;
+use crate::nnue::Network;
+use crate::search::tt::{DEFAULT_SIZE_MB, MAX_SIZE_MB, TranspositionTable};
+use crate::search::{Limits, Report, Search, mate_in};
+
+const NAME: &str = concat!("tinatin ", env!("CARGO_PKG_VERSION"));
+const AUTHOR: &str = "datawater";
+
+// Time kept in reserve so we never lose on time because of overhead
+const MOVE_OVERHEAD: Duration = Duration::from_millis(20);
+// Assumed moves left in the game when the GUI doesn't tell us
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+// Output shared with the search thread. Every `write_fmt` takes the lock once, so lines written
+// with `writeln!` never interleave.
+struct SharedWriter<W>(Arc<Mutex<W>>);
+
+impl<W> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<W: Write> Write for SharedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
+        let mut out = self.0.lock().unwrap();
+        out.write_fmt(args)?;
+        out.flush()
+    }
+}
+
+pub struct Uci<W: Write> {
+    board: Board,
+    out: SharedWriter<W>,
+    stop: Arc<AtomicBool>,
+    // Set by `go ponder` until `ponderhit`
+    ponder: Arc<AtomicBool>,
+    tt: Arc<TranspositionTable>,
+    // Set with the EvalFile option, the hand written eval is used without one
+    network: Option<Arc<Network>>,
+    search: Option<JoinHandle<()>>,
+}
+
+impl<W: Write + Send + 'static> Uci<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            board: Board::new_starting(),
+            out: SharedWriter(Arc::new(Mutex::new(out))),
+            stop: Arc::new(AtomicBool::new(false)),
+            ponder: Arc::new(AtomicBool::new(false)),
+            tt: Arc::new(TranspositionTable::new(DEFAULT_SIZE_MB)),
+            network: None,
+            search: None,
+        }
+    }
+
+    // Runs until `quit` or the end of the input. A search still running at the end of the input
+    // is waited for, so piping in commands prints its result.
+    pub fn run(&mut self, input: impl BufRead) -> io::Result<()> {
+        for line in input.lines() {
+            if !self.handle(&line?)? {
+                break;
+            }
+        }
+
+        self.wait();
+        Ok(())
+    }
+
+    // Returns false once the engine should quit
+    pub fn handle(&mut self, line: &str) -> io::Result<bool> {
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("uci") => {
+                writeln!(self.out, "id name {NAME}")?;
+                writeln!(self.out, "id author {AUTHOR}")?;
+                writeln!(
+                    self.out,
+                    "option name Hash type spin default {DEFAULT_SIZE_MB} min 1 max {MAX_SIZE_MB}"
+                )?;
+                writeln!(self.out, "option name EvalFile type string default <empty>")?;
+                writeln!(self.out, "uciok")?;
+            }
+            Some("isready") => writeln!(self.out, "readyok")?,
+            Some("ucinewgame") => {
+                self.stop();
+                self.board = Board::new_starting();
+                self.tt_mut().clear();
+            }
+            Some("position") => {
+                if let Err(e) = self.position(tokens) {
+                    writeln!(self.out, "info string {e}")?;
+                }
+            }
+            Some("go") => self.go(tokens)?,
+            Some("stop") => self.stop(),
+            Some("ponderhit") => self.ponder.store(false, Ordering::Relaxed),
+            Some("setoption") => self.set_option(tokens)?,
+            Some("d") => writeln!(self.out, "{}", self.board)?,
+            Some("quit") => {
+                self.stop();
+                return Ok(false);
+            }
+
+            None => {}
+            Some(command) => writeln!(self.out, "info string unknown command '{command}'")?,
+        }
+
+        self.out.flush()?;
+        Ok(true)
+    }
+
+    fn position<'a>(&mut self, mut tokens: impl Iterator<Item = &'a str>) -> Result<(), String> {
+        let mut board = match tokens.next() {
+            Some("startpos") => Board::new_starting(),
+            Some("fen") => {
+                let fen = tokens
+                    .by_ref()
+                    .take_while(|&t| t != "moves")
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                Board::from_str(&fen).map_err(|e| e.to_string())?
+            }
+
+            _ => return Err("expected 'startpos' or 'fen'".to_owned()),
+        };
+
+        // `take_while` already ate "moves" after a FEN
+        for token in tokens.skip_while(|&t| t == "moves") {
+            let m = board
+                .parse_uci_move(token)
+                .ok_or_else(|| format!("illegal move '{token}'"))?;
+
+            board.make_move(&m);
+        }
+
+        self.board = board;
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait();
+    }
+
+    fn wait(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.join().expect("search thread panicked");
+        }
+    }
+
+    // Only the search thread shares the table, so this must not be called while it runs
+    fn tt_mut(&mut self) -> &mut TranspositionTable {
+        Arc::get_mut(&mut self.tt).expect("the table is not shared outside of a search")
+    }
+
+    fn go<'a>(&mut self, mut tokens: impl Iterator<Item = &'a str>) -> io::Result<()> {
+        self.stop();
+
+        let mut limits = Limits::default();
+        let (mut time, mut inc, mut moves_to_go) = (None, 0, DEFAULT_MOVES_TO_GO);
+        let mut ponder = false;
+        let white = self.board.side_to_move == crate::types::Color::WHITE;
+
+        while let Some(token) = tokens.next() {
+            let mut value = || tokens.next().and_then(|v| v.parse::<u64>().ok());
+
+            match token {
+                "perft" => return self.perft(value().unwrap_or(1) as u32),
+                "depth" => limits.depth = value().map(|d| d as u32),
+                "nodes" => limits.nodes = value(),
+                "movetime" => limits.time = value().map(Duration::from_millis),
+                "wtime" | "btime" => {
+                    let v = value();
+                    if (token == "wtime") == white {
+                        time = v;
+                    }
+                }
+                "winc" | "binc" => {
+                    let v = value();
+                    if (token == "winc") == white {
+                        inc = v.unwrap_or(0);
+                    }
+                }
+                "movestogo" => moves_to_go = value().map_or(moves_to_go, |m| m.max(1) as u32),
+                "infinite" => limits.infinite = true,
+                "ponder" => ponder = true,
+                _ => {}
+            }
+        }
+
+        if let (None, Some(time)) = (limits.time, time) {
+            let time = Duration::from_millis(time);
+            let budget = time / moves_to_go + Duration::from_millis(inc) / 2;
+            limits.time = Some(
+                budget
+                    .min(time.saturating_sub(MOVE_OVERHEAD))
+                    .max(Duration::from_millis(1)),
+            );
+        }
+
+        self.stop.store(false, Ordering::Relaxed);
+        self.ponder.store(ponder, Ordering::Relaxed);
+        let board = self.board.clone();
+        let stop = Arc::clone(&self.stop);
+        let ponder = Arc::clone(&self.ponder);
+        let tt = Arc::clone(&self.tt);
+        let network = self.network.clone();
+        let mut out = self.out.clone();
+
+        self.search = Some(std::thread::spawn(move || {
+            let mut info_out = out.clone();
+            let mut search = Search::new(board, limits, &stop, &tt).with_ponder(&ponder);
+            if let Some(network) = &network {
+                search = search.with_network(network);
+            }
+
+            let result = search.run(|report| {
+                // Nowhere to report a failed write to, the next command will hit it too
+                let _ = writeln!(info_out, "{}", Info(report));
+            });
+
+            let _ = match result.best_move {
+                Some(m) => writeln!(out, "bestmove {m}"),
+                None => writeln!(out, "bestmove 0000"),
+            };
+        }));
+
+        Ok(())
+    }
+
+    fn perft(&mut self, depth: u32) -> io::Result<()> {
+        let mut nodes = 0;
+        for (m, n) in self.board.divide(depth) {
+            writeln!(self.out, "{m}: {n}")?;
+            nodes += n;
+        }
+
+        writeln!(self.out, "\nNodes searched: {nodes}")
+    }
+
+    fn set_option<'a>(&mut self, tokens: impl Iterator<Item = &'a str>) -> io::Result<()> {
+        let tokens = tokens.collect::<Vec<_>>();
+        let value_at = tokens.iter().position(|&t| t == "value");
+
+        let name = tokens[..value_at.unwrap_or(tokens.len())]
+            .iter()
+            .skip_while(|&&t| t == "name")
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = value_at.map(|i| tokens[i + 1..].join(" "));
+
+        match name.to_ascii_lowercase().as_str() {
+            "hash" => match value.and_then(|v| v.parse::<usize>().ok()) {
+                Some(mb) if (1..=MAX_SIZE_MB).contains(&mb) => {
+                    self.stop();
+                    self.tt = Arc::new(TranspositionTable::new(mb));
+                    Ok(())
+                }
+                _ => writeln!(self.out, "info string invalid Hash value"),
+            },
+            "evalfile" => match value.as_deref() {
+                None | Some("" | "<empty>") => {
+                    self.network = None;
+                    Ok(())
+                }
+                Some(path) => match Network::load(path) {
+                    Ok(network) => {
+                        self.network = Some(Arc::new(network));
+                        Ok(())
+                    }
+                    Err(e) => writeln!(self.out, "info string could not load {path}: {e}"),
+                },
+            },
+
+            _ => writeln!(self.out, "info string unknown option '{name}'"),
+        }
+    }
+}
+
+// A `Report` formatted as a UCI info line
+struct Info<'a>(&'a Report);
+
+impl fmt::Display for Info<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = self.0;
+        let millis = report.time.as_millis();
+
+        write!(f, "info depth {}", report.depth)?;
+        match mate_in(report.score) {
+            Some(moves) => write!(f, " score mate {moves}")?,
+            None => write!(f, " score cp {}", report.score)?,
+        }
+        write!(
+            f,
+            " nodes {} nps {} hashfull {} time {millis} pv",
+            report.nodes,
+            u128::from(report.nodes) * 1000 / millis.max(1),
+            report.hashfull
+        )?;
+
+        for m in &report.pv {
+            write!(f, " {m}")?;
+        }
+
+        Ok(())
+    }
+}
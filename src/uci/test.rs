@@ -0,0 +1,163 @@
+#[cfg(test)]
+use super::*;
+use crate::search::MAX_PLY;
+use crate::types::{Piece, Square};
+use std::time::Instant;
+
+fn run(commands: &str) -> (Uci<Vec<u8>>, String) {
+    let mut uci = Uci::new(Vec::new());
+    uci.run(commands.as_bytes()).unwrap();
+
+    let output = String::from_utf8(std::mem::take(&mut *uci.out.0.lock().unwrap())).unwrap();
+    (uci, output)
+}
+
+#[test]
+fn test_handshake() {
+    let (_, output) = run("uci\nisready\n");
+    let lines = output.lines().collect::<Vec<_>>();
+
+    assert!(lines[0].starts_with("id name tinatin"));
+    assert!(lines.contains(&"uciok"));
+    assert_eq!(lines.last(), Some(&"readyok"));
+}
+
+#[test]
+fn test_position_startpos_moves() {
+    let (uci, _) = run("position startpos moves e2e4 e7e5 g1f3\n");
+    let expected =
+        Board::from_str("rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2").unwrap();
+
+    assert_eq!(uci.board.mailbox, expected.mailbox);
+    assert_eq!(uci.board.side_to_move, expected.side_to_move);
+    assert_eq!(uci.board.state.rule_50, expected.state.rule_50);
+}
+
+#[test]
+fn test_position_fen_moves() {
+    let (uci, _) = run("position fen 4k3/1P6/8/8/8/8/8/4K3 w - - 0 1 moves b7b8n\n");
+
+    assert_eq!(uci.board.mailbox[Square::B8 as usize], Piece::WKnight);
+}
+
+#[test]
+fn test_illegal_move_keeps_position() {
+    let (uci, output) = run("position startpos moves e2e4\nposition startpos moves e2e5\n");
+
+    assert!(output.contains("illegal move 'e2e5'"));
+    assert_eq!(uci.board.mailbox[Square::E4 as usize], Piece::WPawn);
+}
+
+#[test]
+fn test_go_and_quit() {
+    let (_, output) = run("position startpos\ngo\nquit\nisready\n");
+
+    assert!(output.lines().last().unwrap().starts_with("bestmove "));
+    assert!(!output.contains("readyok"));
+}
+
+#[test]
+fn test_go_depth() {
+    let (_, output) = run("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 3\n");
+    let lines = output.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines[2].starts_with("info depth 3 score mate 1 nodes "));
+    assert!(lines[2].ends_with(" pv a1a8"));
+    assert_eq!(lines[3], "bestmove a1a8");
+}
+
+#[test]
+fn test_go_infinite_until_stop() {
+    let (_, output) = run("position startpos\ngo infinite\nisready\nstop\n");
+    let lines = output.lines().collect::<Vec<_>>();
+
+    assert!(lines.contains(&"readyok"));
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+
+    // Bare kings run out of depth to search right away, and still have to wait for `stop`
+    let mut uci = Uci::new(Vec::new());
+    uci.handle("position fen 4k3/8/8/8/8/8/8/4K3 w - - 0 1")
+        .unwrap();
+    uci.handle("go infinite").unwrap();
+
+    let output = |uci: &Uci<Vec<u8>>| String::from_utf8(uci.out.0.lock().unwrap().clone()).unwrap();
+    let start = Instant::now();
+    while !output(&uci).contains(&format!("info depth {} ", MAX_PLY - 1)) {
+        assert!(start.elapsed() < Duration::from_mins(1), "{}", output(&uci));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!output(&uci).contains("bestmove"));
+
+    uci.handle("stop").unwrap();
+    assert!(
+        output(&uci)
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("bestmove ")
+    );
+}
+
+#[test]
+fn test_go_ponder_until_ponderhit() {
+    let output = |uci: &Uci<Vec<u8>>| String::from_utf8(uci.out.0.lock().unwrap().clone()).unwrap();
+
+    // Pondering ignores the clock, which would be long gone by now
+    let mut uci = Uci::new(Vec::new());
+    uci.handle("position startpos").unwrap();
+    uci.handle("go ponder wtime 100 btime 100").unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(!output(&uci).contains("bestmove"));
+
+    // and starts it on `ponderhit`
+    uci.handle("ponderhit").unwrap();
+    let start = Instant::now();
+    while !output(&uci).contains("bestmove ") {
+        assert!(start.elapsed() < Duration::from_mins(1), "{}", output(&uci));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    // Bare kings run out of depth to search while pondering, then wait for `ponderhit`
+    uci.handle("position fen 4k3/8/8/8/8/8/8/4K3 w - - 0 1")
+        .unwrap();
+    uci.handle("go ponder wtime 100000 btime 100000").unwrap();
+    let start = Instant::now();
+    while !output(&uci).contains(&format!("info depth {} ", MAX_PLY - 1)) {
+        assert!(start.elapsed() < Duration::from_mins(1), "{}", output(&uci));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(output(&uci).matches("bestmove").count(), 1);
+
+    uci.handle("ponderhit").unwrap();
+    let start = Instant::now();
+    while output(&uci).matches("bestmove").count() < 2 {
+        assert!(start.elapsed() < Duration::from_mins(1), "{}", output(&uci));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_hash_option() {
+    let (uci, output) = run("uci\nsetoption name Hash value 2\n");
+    assert!(output.contains("option name Hash type spin default 16 min 1 max 65536"));
+    assert!(!output.contains("info string"));
+    assert_eq!(uci.tt.probe(Board::new_starting().hash()), None);
+
+    let (_, output) = run("setoption name Hash value 0\nsetoption name Hash value lots\n");
+    assert_eq!(output.matches("invalid Hash value").count(), 2);
+}
+
+#[test]
+fn test_eval_file_option() {
+    let (uci, output) = run("uci\nsetoption name EvalFile value /nonexistent/net.bin\n");
+    assert!(output.contains("option name EvalFile type string default <empty>"));
+    assert!(output.contains("info string could not load /nonexistent/net.bin"));
+    assert!(uci.network.is_none());
+
+    // Still searches with the hand written eval
+    let (_, output) = run("setoption name EvalFile value <empty>\ngo depth 1\n");
+    assert!(output.lines().last().unwrap().starts_with("bestmove"));
+}
@@ -49,3 +49,13 @@ pub const fn maxi8(a: i8, b: i8) -> i8 {
 pub const fn mini8(a: i8, b: i8) -> i8 {
     if a < b { a } else { b }
 }
+
+// Seeded pseudo-random numbers, for the Zobrist keys and the magic finder
+pub const fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}